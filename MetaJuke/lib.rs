@@ -1,9 +1,45 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contractimpl, contracttype, token, Address, BytesN, Env, FromVal, Map, String,
-    Symbol, TryIntoVal, Vec,
+    contract, contracterror, contractimpl, contracttype, panic_with_error, token, Address, Bytes,
+    BytesN, Env, FromVal, IntoVal, Map, String, Symbol, TryFromVal, TryIntoVal, Val, Vec,
 };
 
+const BASE_REPUTATION: u32 = 100;
+const MAX_PLATFORM_FEE: u32 = 2000;
+/// Indexed list entries live in persistent storage; every read or write of an
+/// entry pushes its TTL back out to `LIST_TTL_EXTEND` ledgers.
+const LIST_TTL_THRESHOLD: u32 = 17280;
+const LIST_TTL_EXTEND: u32 = 518400;
+/// Platform-wide ceiling on a table's queue, applied even when the owner leaves
+/// `max_queue_len` at 0.
+const MAX_QUEUE_LEN: u32 = 100;
+const MAX_TRACK_TAGS: u32 = 10;
+/// Largest `limit` accepted by the paginated list views. Each view takes a
+/// `cursor` (start at 0) and returns the page plus the cursor of the next page,
+/// or `None` once the list is exhausted.
+const MAX_PAGE_SIZE: u32 = 50;
+const MAX_ARTIST_NAME_LEN: u32 = 32;
+const MAX_ARTIST_LINKS: u32 = 5;
+const MAX_FAVORITES: u32 = 100;
+const MAX_PLAYLIST_TRACKS: u32 = 50;
+const MAX_USER_PLAYLISTS: u32 = 20;
+const NAME_RELEASE_COOLDOWN: u64 = 30 * 86400;
+const LEADERBOARD_SIZE: u32 = 10;
+/// Rolling boards keep extra candidates so entries that age out can be
+/// replaced without waiting for the next update.
+const LEADERBOARD_CANDIDATES: u32 = 2 * LEADERBOARD_SIZE;
+const MAX_RENEWAL_PERIODS: u32 = 12;
+const RENEWAL_WINDOW: u64 = 86400;
+const CAMPAIGN_ACTIVATION_GRACE: u64 = 7 * 86400;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum LimitError {
+    MemberLimitReached = 1,
+    QueueLimitReached = 2,
+    PendingLimitReached = 3,
+}
 
 #[contracttype]
 #[derive(Clone)]
@@ -11,6 +47,15 @@ pub struct TableMembership {
     member: Address,
     joined_at: u64,
     is_admin: bool,
+    expires_at: Option<u64>,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct MembershipPlan {
+    entry_fee: i128,
+    period_price: i128,
+    period_length: u64,
 }
 
 #[contracttype]
@@ -19,6 +64,7 @@ pub struct User {
     profile_nft: Address,
     avatar_uri: String,
     reputation: u32,
+    reputation_updated_at: u64,
     is_active: bool,
 }
 
@@ -29,6 +75,33 @@ pub struct Artist {
     artist_name: String,
     revenue_balance: i128,
     verified: bool,
+    bio_uri: Option<String>,
+    links: Vec<String>,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct EarningsStat {
+    earned: i128,
+    requests: u32,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct ArtistStatement {
+    artist: Address,
+    token: Address,
+    revenue_balance: i128,
+    lifetime: EarningsStat,
+    track: Option<EarningsStat>,
+    table: Option<EarningsStat>,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct NameReservation {
+    artist: Address,
+    released_at: Option<u64>,
 }
 
 #[contracttype]
@@ -44,6 +117,33 @@ pub struct JukeboxTable {
     price_multiplier: u32,
     member_count: u32,
     is_active: bool,
+    current_request: Option<BytesN<32>>,
+    pending_requests: Vec<BytesN<32>>,
+    min_reputation: u32,
+    reputation_weighted_skips: bool,
+    visibility: TableVisibility,
+    max_members: u32,
+    max_queue_len: u32,
+    max_pending_per_user: u32,
+    owner_share_bps: Option<u32>,
+    total_requests: u32,
+    total_plays: u32,
+    total_revenue: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TableVisibility {
+    Public,
+    Allowlist,
+    InviteCode,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct InviteCode {
+    max_uses: u32,
+    uses: u32,
 }
 
 #[contracttype]
@@ -59,6 +159,80 @@ pub struct Track {
     licenses_remaining: u32,
     metadata_uri: String,
     royalty_split: Vec<(Address, u32)>,
+    resale_royalty_bps: u32,
+    metadata_version: u32,
+    tags: Vec<Symbol>,
+    max_venue_markup_bps: Option<u32>,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TagKind {
+    Genre,
+    Mood,
+    Explicit,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Tag {
+    tag: Symbol,
+    kind: TagKind,
+    label: String,
+    active: bool,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct TableTagRules {
+    allowed_genres: Vec<Symbol>,
+    allow_explicit: bool,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct TrackDraft {
+    title: String,
+    base_price: i128,
+    licenses: u32,
+    metadata_uri: String,
+    metadata_hash: BytesN<32>,
+    collaborators: Vec<Address>,
+    royalty_split: Vec<(Address, u32)>,
+    tags: Vec<Symbol>,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum CampaignStatus {
+    Active,
+    Funded,
+    Failed,
+}
+
+/// Backers buy the `backer_share_bps` slice of the royalty split in whole
+/// percentage points, so each point costs `goal / (backer_share_bps / 100)`.
+#[contracttype]
+#[derive(Clone)]
+pub struct FundingCampaign {
+    campaign_id: BytesN<32>,
+    artist: Address,
+    draft: TrackDraft,
+    goal: i128,
+    raised: i128,
+    deadline: u64,
+    backer_share_bps: u32,
+    status: CampaignStatus,
+    track_id: Option<BytesN<32>>,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct MetadataVersion {
+    version: u32,
+    uri: String,
+    content_hash: BytesN<32>,
+    created_at: u64,
 }
 
 #[contracttype]
@@ -70,6 +244,247 @@ pub struct TrackRequest {
     table_id: BytesN<32>,
     timestamp: u64,
     amount_paid: i128,
+    status: RequestStatus,
+    metadata_version: u32,
+    venue_cut: i128,
+    discount: i128,
+    points_redeemed: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum RequestStatus {
+    Queued,
+    Playing,
+    Played,
+    Skipped,
+    Removed,
+    Refunded,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum LicenseTier {
+    Personal,
+    Venue,
+    Broadcast,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct LicenseTierConfig {
+    price: i128,
+    supply: u32,
+    sold: u32,
+    duration: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct License {
+    license_id: BytesN<32>,
+    track_id: BytesN<32>,
+    tier: LicenseTier,
+    holder: Address,
+    table_id: Option<BytesN<32>>,
+    purchased_at: u64,
+    expires_at: Option<u64>,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct LicenseListing {
+    license_id: BytesN<32>,
+    seller: Address,
+    price: i128,
+    listed_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TakedownStatus {
+    Active,
+    Contested,
+    Upheld,
+    Released,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Takedown {
+    track_id: BytesN<32>,
+    moderator: Address,
+    reason_uri: String,
+    counter_claim_uri: Option<String>,
+    taken_down_at: u64,
+    status: TakedownStatus,
+    escrowed: i128,
+    escrow_shares: Map<Address, i128>,
+    /// Settled request payments for the track. Once upheld, requesters are
+    /// refunded pro rata out of at most this much of the escrow; the rest,
+    /// which came from tips and license sales, goes to the admin.
+    refund_base: i128,
+    refund_cursor: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum VerificationStatus {
+    Pending,
+    Approved,
+    Rejected,
+    Revoked,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct VerificationRecord {
+    record_id: u32,
+    artist: Address,
+    evidence_uri: Option<String>,
+    submitted_at: u64,
+    status: VerificationStatus,
+    reviewer: Option<Address>,
+    reason: Option<String>,
+    reviewed_at: Option<u64>,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct VerificationPrivileges {
+    unverified_price_cap: i128,
+    verified_price_cap: i128,
+    max_featured_per_artist: u32,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct ListenerStats {
+    requests_made: u32,
+    total_spent: i128,
+    skips_cast: u32,
+    plays_completed: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum LeaderboardKind {
+    MostPlayed,
+    HighestGrossing,
+    BusiestTables,
+    TopRequesters,
+}
+
+/// Rolling boards count activity over the trailing 24 hours (hourly buckets)
+/// or 7 days (daily buckets); the all-time board never resets.
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum LeaderboardWindow {
+    AllTime,
+    LastDay,
+    LastWeek,
+}
+
+#[contracttype]
+#[derive(Clone, PartialEq, Eq)]
+pub enum LeaderboardSubject {
+    Track(BytesN<32>),
+    Table(BytesN<32>),
+    User(Address),
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct LeaderboardEntry {
+    subject: LeaderboardSubject,
+    score: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Playlist {
+    playlist_id: BytesN<32>,
+    owner: Address,
+    name: String,
+    tracks: Vec<BytesN<32>>,
+    is_public: bool,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct TableSummary {
+    table_id: BytesN<32>,
+    name: String,
+    owner: Address,
+    archived_at: u64,
+    member_count: u32,
+    total_requests: u32,
+    total_plays: u32,
+    total_revenue: i128,
+    cleanup_complete: bool,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Suspension {
+    moderator: Address,
+    reason_uri: String,
+    suspended_at: u64,
+    expires_at: Option<u64>,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct ReferralConfig {
+    reward_bps: u32,
+    period: u64,
+    cap: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Referral {
+    referrer: Address,
+    referred_at: u64,
+    earned: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct ReferrerStats {
+    referrals: u32,
+    earned: i128,
+}
+
+/// Points are worth `point_value` tokens each when redeemed, and a single
+/// request can be discounted by at most `max_discount_bps` of its price.
+#[contracttype]
+#[derive(Clone)]
+pub struct LoyaltyConfig {
+    points_per_request: i128,
+    points_per_play: i128,
+    point_value: i128,
+    max_discount_bps: u32,
+    expiry_period: u64,
+}
+
+/// The whole balance expires once `expires_at` passes; earning points pushes
+/// the expiry out by another `expiry_period`.
+#[contracttype]
+#[derive(Clone)]
+pub struct LoyaltyAccount {
+    points: i128,
+    expires_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct ReputationConfig {
+    play_reward: u32,
+    skip_penalty: u32,
+    decay_interval: u64,
+    decay_step: u32,
+    max_reputation: u32,
 }
 
 #[contracttype]
@@ -81,6 +496,58 @@ pub enum ContractEvent {
     AdminChanged(BytesN<32>, Address, bool),
     TableStatusChanged(BytesN<32>, bool),
     SkipVoted(BytesN<32>, Address),
+    UserSuspended(Address, Option<u64>),
+    UserReinstated(Address),
+    MemberKicked(BytesN<32>, Address),
+    MemberBanned(BytesN<32>, Address, u64),
+    RequestRemoved(BytesN<32>, BytesN<32>),
+    BlocklistChanged(BytesN<32>, bool),
+    MembershipRenewed(BytesN<32>, Address, u64),
+    OwnershipProposed(BytesN<32>, Address),
+    OwnershipTransferred(BytesN<32>, Address, Address),
+    TableArchived(BytesN<32>),
+    LicensePurchased(BytesN<32>, BytesN<32>, LicenseTier),
+    LicenseListed(BytesN<32>, Address, i128),
+    LicenseSold(BytesN<32>, Address, Address, i128),
+    MetadataUpdated(BytesN<32>, u32),
+    TrackTakenDown(BytesN<32>, i128),
+    CounterClaimFiled(BytesN<32>),
+    TakedownResolved(BytesN<32>, bool),
+    VerificationSubmitted(Address, u32),
+    VerificationReviewed(Address, u32, bool),
+    ArtistRenamed(Address, String),
+    ReputationChanged(Address, u32),
+    TipSent(BytesN<32>, BytesN<32>, Address, i128),
+    ReferralRewarded(Address, Address, i128),
+    LoyaltyPointsChanged(Address, i128),
+    CampaignCreated(BytesN<32>, Address),
+    CampaignBacked(BytesN<32>, Address, i128),
+    CampaignFunded(BytesN<32>, BytesN<32>),
+    CampaignRefunded(BytesN<32>, Address, i128),
+}
+
+/// Lists stored one persistent entry per key so views can read a single page
+/// without loading the whole list. Removal swaps the last entry into the freed
+/// slot.
+#[contracttype]
+#[derive(Clone)]
+enum IndexedList {
+    TableMembers(BytesN<32>),
+    TableRequests(BytesN<32>),
+    ArtistTracks(Address),
+    UserTables(Address),
+    TagTracks(Symbol),
+    UserPlaylists(Address),
+    FavoriteTracks(Address),
+    FavoriteArtists(Address),
+    FeaturedTracks,
+    ArtistFeaturedTracks(Address),
+    TableBans(BytesN<32>),
+    TableAllowlist(BytesN<32>),
+    TableInvites(BytesN<32>),
+    TableBlockedTracks(BytesN<32>),
+    TableBlockedArtists(BytesN<32>),
+    TableLicenses(BytesN<32>),
 }
 
 #[contracttype]
@@ -99,6 +566,66 @@ enum DataKey {
     TrackIdCounter,
     TableIdCounter,
     RequestIdCounter,
+    ReputationConfig,
+    ArtistTracks(Address, BytesN<32>),
+    TableMembers(BytesN<32>, Address),
+    TableAdmins(BytesN<32>, Address),
+    TableAdminList(BytesN<32>),
+    UserTables(Address, BytesN<32>),
+    Moderators(Address),
+    Suspensions(Address),
+    TableBans(BytesN<32>, Address),
+    TableBlockedTracks(BytesN<32>, BytesN<32>),
+    TableBlockedArtists(BytesN<32>, Address),
+    TableMembershipPlans(BytesN<32>),
+    TableAllowlist(BytesN<32>, Address),
+    TableInvites(BytesN<32>, BytesN<32>),
+    PendingRequestCount(BytesN<32>, Address),
+    PendingTableOwners(BytesN<32>),
+    TableSummaries(BytesN<32>),
+    Playlists(BytesN<32>),
+    PlaylistIdCounter,
+    TrackLicenseTiers(BytesN<32>, LicenseTier),
+    Licenses(BytesN<32>),
+    TableLicenses(BytesN<32>, BytesN<32>),
+    HolderLicenses(Address, BytesN<32>, LicenseTier),
+    LicenseIdCounter,
+    LicenseListings(BytesN<32>),
+    TrackMetadataVersions(BytesN<32>, u32),
+    TrackBalances(BytesN<32>, Address),
+    ArtistEarningTracks(Address),
+    SettledRequests(BytesN<32>, u32),
+    SettledCount(BytesN<32>),
+    SettledTotal(BytesN<32>),
+    Takedowns(BytesN<32>),
+    Tags(Symbol),
+    TableTagRules(BytesN<32>),
+    Verifiers(Address),
+    VerificationRecords(Address, u32),
+    VerificationCount(Address),
+    VerificationPrivileges,
+    ArtistNames(Bytes),
+    ListLength(IndexedList),
+    ListItems(IndexedList, u32),
+    ListPositions(IndexedList, Val),
+    ArtistTrackEarnings(Address, BytesN<32>),
+    ArtistTableEarnings(Address, BytesN<32>),
+    ArtistTokenEarnings(Address, Address),
+    ListenerStats(Address),
+    Leaderboards(LeaderboardKind, LeaderboardWindow),
+    LeaderboardScores(LeaderboardKind, LeaderboardWindow, LeaderboardSubject),
+    TipFee,
+    TrackTips(BytesN<32>),
+    OwnerBalances(Address),
+    ReferralConfig,
+    Referrals(Address),
+    ReferrerStats(Address),
+    LoyaltyConfig,
+    LoyaltyAccounts(Address),
+    Campaigns(BytesN<32>),
+    CampaignIdCounter,
+    CampaignBackers(BytesN<32>),
+    CampaignContributions(BytesN<32>, Address),
 }
 
 #[contract]
@@ -113,6 +640,10 @@ impl MetaJuke {
 
         admin.require_auth();
 
+        if platform_fee > MAX_PLATFORM_FEE {
+            panic!("Fee too high");
+        }
+
         env.storage().instance().set(&DataKey::Admin, &admin);
         env.storage()
             .instance()
@@ -130,87 +661,438 @@ impl MetaJuke {
             .instance()
             .set(&DataKey::RequestIdCounter, &0u32);
         env.storage().instance().set(&DataKey::UserCounter, &0u32);
+        env.storage().instance().set(
+            &DataKey::ReputationConfig,
+            &ReputationConfig {
+                play_reward: 2,
+                skip_penalty: 5,
+                decay_interval: 86400,
+                decay_step: 1,
+                max_reputation: 1000,
+            },
+        );
     }
 
     pub fn update_platform_fee(env: Env, new_fee: u32) {
         let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
         admin.require_auth();
 
-        if new_fee > 2000 {
+        if new_fee > MAX_PLATFORM_FEE {
             panic!("Fee too high");
         }
 
+        // Loyalty discounts are paid out of the fee, so it may not drop below
+        // the largest discount a request can carry.
+        if let Some(loyalty) = env
+            .storage()
+            .instance()
+            .get::<_, LoyaltyConfig>(&DataKey::LoyaltyConfig)
+        {
+            if new_fee < loyalty.max_discount_bps {
+                panic!("Fee below loyalty discount limit");
+            }
+        }
+
         env.storage()
             .instance()
             .set(&DataKey::PlatformFee, &new_fee);
     }
 
-    pub fn register_user(env: Env, user: Address, profile_nft: Address, avatar_uri: String) {
-        user.require_auth();
+    pub fn update_tip_fee(env: Env, new_fee: u32) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
 
-        if !Self::verify_nft_ownership(&env, &user, &profile_nft) {
-            panic!("User doesn't own the NFT");
+        let platform_fee: u32 = env.storage().instance().get(&DataKey::PlatformFee).unwrap();
+        if new_fee > platform_fee {
+            panic!("Tip fee cannot exceed platform fee");
         }
 
-        if env.storage().instance().has(&DataKey::Users(user.clone())) {
-            panic!("User already registered");
-        }
+        env.storage().instance().set(&DataKey::TipFee, &new_fee);
+    }
 
-        if env
-            .storage()
-            .instance()
-            .has(&DataKey::NftToUser(profile_nft.clone()))
-        {
-            panic!("NFT already associated with another user");
+    pub fn update_reputation_config(
+        env: Env,
+        play_reward: u32,
+        skip_penalty: u32,
+        decay_interval: u64,
+        decay_step: u32,
+        max_reputation: u32,
+    ) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        if max_reputation < BASE_REPUTATION {
+            panic!("Max reputation below base reputation");
         }
 
-        let new_user = User {
-            profile_nft: profile_nft.clone(),
-            avatar_uri,
-            reputation: 100,
-            is_active: true,
-        };
+        env.storage().instance().set(
+            &DataKey::ReputationConfig,
+            &ReputationConfig {
+                play_reward,
+                skip_penalty,
+                decay_interval,
+                decay_step,
+                max_reputation,
+            },
+        );
+    }
 
-        env.storage()
-            .instance()
-            .set(&DataKey::Users(user.clone()), &new_user);
-        env.storage()
-            .instance()
-            .set(&DataKey::UserToNft(user.clone()), &profile_nft);
-        env.storage()
-            .instance()
-            .set(&DataKey::NftToUser(profile_nft), &user);
+    /// Referrers earn `reward_bps` of the platform fee on their referees'
+    /// requests for `period` seconds after the referee registers, up to `cap`
+    /// per referee.
+    pub fn update_referral_config(env: Env, reward_bps: u32, period: u64, cap: i128) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        if reward_bps > 10000 {
+            panic!("Invalid referral reward");
+        }
+        if cap < 0 {
+            panic!("Invalid referral cap");
+        }
+
+        env.storage().instance().set(
+            &DataKey::ReferralConfig,
+            &ReferralConfig {
+                reward_bps,
+                period,
+                cap,
+            },
+        );
     }
 
-    pub fn register_artist(env: Env, user: Address, artist_name: String) {
-        user.require_auth();
+    pub fn update_loyalty_config(
+        env: Env,
+        points_per_request: i128,
+        points_per_play: i128,
+        point_value: i128,
+        max_discount_bps: u32,
+        expiry_period: u64,
+    ) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
 
-        if !env.storage().instance().has(&DataKey::Users(user.clone())) {
-            panic!("User not registered");
+        let platform_fee: u32 = env.storage().instance().get(&DataKey::PlatformFee).unwrap();
+        if max_discount_bps > platform_fee {
+            panic!("Discount cannot exceed platform fee");
+        }
+        if points_per_request < 0 || points_per_play < 0 || point_value <= 0 {
+            panic!("Invalid loyalty config");
         }
 
-        if env
-            .storage()
+        env.storage().instance().set(
+            &DataKey::LoyaltyConfig,
+            &LoyaltyConfig {
+                points_per_request,
+                points_per_play,
+                point_value,
+                max_discount_bps,
+                expiry_period,
+            },
+        );
+    }
+
+    pub fn get_loyalty_config(env: Env) -> Option<LoyaltyConfig> {
+        env.storage().instance().get(&DataKey::LoyaltyConfig)
+    }
+
+    pub fn get_loyalty_points(env: Env, user: Address) -> LoyaltyAccount {
+        Self::loyalty_account(&env, &user)
+    }
+
+    pub fn add_moderator(env: Env, moderator: Address) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        env.storage()
             .instance()
-            .has(&DataKey::Artists(user.clone()))
-        {
-            panic!("Already registered as artist");
-        }
+            .set(&DataKey::Moderators(moderator), &true);
+    }
 
-        let new_artist = Artist {
-            user_id: user.clone(),
-            artist_name,
-            revenue_balance: 0,
-            verified: false,
-        };
+    pub fn remove_moderator(env: Env, moderator: Address) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
 
         env.storage()
             .instance()
-            .set(&DataKey::Artists(user), &new_artist);
+            .remove(&DataKey::Moderators(moderator));
     }
 
-    pub fn update_user_profile(env: Env, user: Address, avatar_uri: String) {
-        user.require_auth();
+    pub fn suspend_user(
+        env: Env,
+        moderator: Address,
+        user: Address,
+        reason_uri: String,
+        expires_at: Option<u64>,
+    ) {
+        Self::require_moderator(&env, &moderator);
+
+        let mut user_data: User = env
+            .storage()
+            .instance()
+            .get(&DataKey::Users(user.clone()))
+            .unwrap_or_else(|| panic!("User not registered"));
+
+        if let Some(expiry) = expires_at {
+            if expiry <= env.ledger().timestamp() {
+                panic!("Suspension expiry must be in the future");
+            }
+        }
+
+        let suspension = Suspension {
+            moderator,
+            reason_uri,
+            suspended_at: env.ledger().timestamp(),
+            expires_at,
+        };
+
+        user_data.is_active = false;
+        env.storage()
+            .instance()
+            .set(&DataKey::Users(user.clone()), &user_data);
+        env.storage()
+            .instance()
+            .set(&DataKey::Suspensions(user.clone()), &suspension);
+
+        env.events()
+            .publish((Symbol::new(&env, "user_suspended"), user), expires_at);
+    }
+
+    pub fn reinstate_user(env: Env, moderator: Address, user: Address) {
+        Self::require_moderator(&env, &moderator);
+
+        let mut user_data: User = env
+            .storage()
+            .instance()
+            .get(&DataKey::Users(user.clone()))
+            .unwrap_or_else(|| panic!("User not registered"));
+
+        if user_data.is_active {
+            panic!("User not suspended");
+        }
+
+        user_data.is_active = true;
+        env.storage()
+            .instance()
+            .set(&DataKey::Users(user.clone()), &user_data);
+        env.storage()
+            .instance()
+            .remove(&DataKey::Suspensions(user.clone()));
+
+        env.events()
+            .publish((Symbol::new(&env, "user_reinstated"), user), ());
+    }
+
+    pub fn add_tag(env: Env, tag: Symbol, kind: TagKind, label: String) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        if env.storage().instance().has(&DataKey::Tags(tag.clone())) {
+            panic!("Tag already exists");
+        }
+
+        env.storage().instance().set(
+            &DataKey::Tags(tag.clone()),
+            &Tag {
+                tag,
+                kind,
+                label,
+                active: true,
+            },
+        );
+    }
+
+    pub fn set_tag_active(env: Env, tag: Symbol, active: bool) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        let mut tag_info: Tag = env
+            .storage()
+            .instance()
+            .get(&DataKey::Tags(tag.clone()))
+            .unwrap_or_else(|| panic!("Unknown tag"));
+
+        tag_info.active = active;
+        env.storage().instance().set(&DataKey::Tags(tag), &tag_info);
+    }
+
+    pub fn register_user(
+        env: Env,
+        user: Address,
+        profile_nft: Address,
+        avatar_uri: String,
+        referrer: Option<Address>,
+    ) {
+        user.require_auth();
+
+        if !Self::verify_nft_ownership(&env, &user, &profile_nft) {
+            panic!("User doesn't own the NFT");
+        }
+
+        if env.storage().instance().has(&DataKey::Users(user.clone())) {
+            panic!("User already registered");
+        }
+
+        if env
+            .storage()
+            .instance()
+            .has(&DataKey::NftToUser(profile_nft.clone()))
+        {
+            panic!("NFT already associated with another user");
+        }
+
+        let new_user = User {
+            profile_nft: profile_nft.clone(),
+            avatar_uri,
+            reputation: BASE_REPUTATION,
+            reputation_updated_at: env.ledger().timestamp(),
+            is_active: true,
+        };
+
+        env.storage()
+            .instance()
+            .set(&DataKey::Users(user.clone()), &new_user);
+        env.storage()
+            .instance()
+            .set(&DataKey::UserToNft(user.clone()), &profile_nft);
+        env.storage()
+            .instance()
+            .set(&DataKey::NftToUser(profile_nft), &user);
+
+        if let Some(referrer) = referrer {
+            if referrer == user {
+                panic!("Cannot refer yourself");
+            }
+            if !env
+                .storage()
+                .instance()
+                .has(&DataKey::Users(referrer.clone()))
+            {
+                panic!("Referrer not registered");
+            }
+
+            let mut stats = Self::get_referrer_stats(env.clone(), referrer.clone());
+            stats.referrals += 1;
+
+            env.storage().instance().set(
+                &DataKey::Referrals(user),
+                &Referral {
+                    referrer: referrer.clone(),
+                    referred_at: env.ledger().timestamp(),
+                    earned: 0,
+                },
+            );
+            env.storage()
+                .instance()
+                .set(&DataKey::ReferrerStats(referrer), &stats);
+        }
+    }
+
+    pub fn get_referral(env: Env, user: Address) -> Option<Referral> {
+        env.storage().instance().get(&DataKey::Referrals(user))
+    }
+
+    pub fn get_referrer_stats(env: Env, referrer: Address) -> ReferrerStats {
+        env.storage()
+            .instance()
+            .get(&DataKey::ReferrerStats(referrer))
+            .unwrap_or(ReferrerStats {
+                referrals: 0,
+                earned: 0,
+            })
+    }
+
+    pub fn register_artist(env: Env, user: Address, artist_name: String) {
+        user.require_auth();
+
+        if !env.storage().instance().has(&DataKey::Users(user.clone())) {
+            panic!("User not registered");
+        }
+
+        if env
+            .storage()
+            .instance()
+            .has(&DataKey::Artists(user.clone()))
+        {
+            panic!("Already registered as artist");
+        }
+
+        Self::claim_artist_name(&env, &user, &artist_name);
+
+        let new_artist = Artist {
+            user_id: user.clone(),
+            artist_name,
+            revenue_balance: 0,
+            verified: false,
+            bio_uri: None,
+            links: Vec::new(&env),
+        };
+
+        env.storage()
+            .instance()
+            .set(&DataKey::Artists(user), &new_artist);
+    }
+
+    pub fn update_artist_profile(
+        env: Env,
+        artist: Address,
+        artist_name: String,
+        bio_uri: Option<String>,
+        links: Vec<String>,
+    ) {
+        artist.require_auth();
+        Self::require_active_user(&env, &artist);
+
+        let mut artist_data: Artist = env
+            .storage()
+            .instance()
+            .get(&DataKey::Artists(artist.clone()))
+            .unwrap_or_else(|| panic!("Not registered as artist"));
+
+        if links.len() > MAX_ARTIST_LINKS {
+            panic!("Too many links");
+        }
+
+        let old_name = Self::normalize_artist_name(&env, &artist_data.artist_name);
+        let new_name = Self::normalize_artist_name(&env, &artist_name);
+        if old_name != new_name {
+            Self::claim_artist_name(&env, &artist, &artist_name);
+            env.storage().instance().set(
+                &DataKey::ArtistNames(old_name),
+                &NameReservation {
+                    artist: artist.clone(),
+                    released_at: Some(env.ledger().timestamp()),
+                },
+            );
+
+            env.events().publish(
+                (Symbol::new(&env, "artist_renamed"), artist.clone()),
+                artist_name.clone(),
+            );
+        }
+
+        artist_data.artist_name = artist_name;
+        artist_data.bio_uri = bio_uri;
+        artist_data.links = links;
+
+        env.storage()
+            .instance()
+            .set(&DataKey::Artists(artist), &artist_data);
+    }
+
+    pub fn resolve_artist_name(env: Env, artist_name: String) -> Option<Address> {
+        let name = Self::normalize_artist_name(&env, &artist_name);
+        let reservation: NameReservation =
+            env.storage().instance().get(&DataKey::ArtistNames(name))?;
+
+        if reservation.released_at.is_some() {
+            return None;
+        }
+        Some(reservation.artist)
+    }
+
+    pub fn update_user_profile(env: Env, user: Address, avatar_uri: String) {
+        user.require_auth();
 
         let mut user_data: User = env
             .storage()
@@ -231,10 +1113,13 @@ impl MetaJuke {
         base_price: i128,
         licenses: u32,
         metadata_uri: String,
+        metadata_hash: BytesN<32>,
         collaborators: Vec<Address>,
         royalty_split: Vec<(Address, u32)>,
+        tags: Vec<Symbol>,
     ) -> BytesN<32> {
         artist.require_auth();
+        Self::require_active_user(&env, &artist);
 
         if !env
             .storage()
@@ -244,14 +1129,46 @@ impl MetaJuke {
             panic!("Not registered as artist");
         }
 
+        let draft = TrackDraft {
+            title,
+            base_price,
+            licenses,
+            metadata_uri,
+            metadata_hash,
+            collaborators,
+            royalty_split,
+            tags,
+        };
+        Self::check_track_draft(&env, &artist, &draft);
+
+        Self::create_track(&env, &artist, draft)
+    }
+
+    fn check_track_draft(env: &Env, artist: &Address, draft: &TrackDraft) {
         let mut total_split = 0;
-        for (_, percentage) in royalty_split.iter() {
+        for (_, percentage) in draft.royalty_split.iter() {
             total_split += percentage;
         }
         if total_split != 100 {
             panic!("Royalty splits must total 100%");
         }
 
+        Self::check_track_tags(env, &draft.tags);
+        Self::check_price_cap(env, artist, draft.base_price);
+    }
+
+    fn create_track(env: &Env, artist: &Address, draft: TrackDraft) -> BytesN<32> {
+        let TrackDraft {
+            title,
+            base_price,
+            licenses,
+            metadata_uri,
+            metadata_hash,
+            collaborators,
+            royalty_split,
+            tags,
+        } = draft;
+
         let mut track_counter: u32 = env
             .storage()
             .instance()
@@ -259,8 +1176,8 @@ impl MetaJuke {
             .unwrap();
         track_counter += 1;
 
-        let track_id_str = String::from_str(&env, "track_");
-        let track_id_bytes: BytesN<32> = BytesN::from_val(&env, &track_id_str.to_val());
+        let track_id_str = String::from_str(env, "track_");
+        let track_id_bytes: BytesN<32> = BytesN::from_val(env, &track_id_str.to_val());
         track_id_bytes.copy_into_slice(
             track_counter
                 .to_be_bytes()
@@ -270,12 +1187,12 @@ impl MetaJuke {
         );
         let track_id: BytesN<32> = env.crypto().sha256((&track_id_bytes).as_ref()).into();
 
-        let track_nft_str = String::from_str(&env, "track_nft_");
-        let track_nft_id_bytes = BytesN::from_val(&env, &track_nft_str.to_val());
+        let track_nft_str = String::from_str(env, "track_nft_");
+        let track_nft_id_bytes = BytesN::from_val(env, &track_nft_str.to_val());
         track_nft_id_bytes.copy_into_slice(&mut track_counter.to_be_bytes());
         let track_nft =
             Address::from_string_bytes(<BytesN<32> as AsRef<soroban_sdk::Bytes>>::as_ref(
-                &BytesN::from_val(&env, &track_nft_id_bytes.to_val()),
+                &BytesN::from_val(env, &track_nft_id_bytes.to_val()),
             ));
 
         let new_track = Track {
@@ -287,123 +1204,3622 @@ impl MetaJuke {
             play_count: 0,
             base_price,
             licenses_remaining: licenses,
-            metadata_uri,
+            metadata_uri: metadata_uri.clone(),
             royalty_split,
+            resale_royalty_bps: 0,
+            metadata_version: 1,
+            tags: tags.clone(),
+            max_venue_markup_bps: None,
         };
 
         env.storage()
             .instance()
             .set(&DataKey::Tracks(track_id.clone()), &new_track);
+        env.storage().instance().set(
+            &DataKey::TrackMetadataVersions(track_id.clone(), 1),
+            &MetadataVersion {
+                version: 1,
+                uri: metadata_uri,
+                content_hash: metadata_hash,
+                created_at: env.ledger().timestamp(),
+            },
+        );
+        env.storage().instance().set(
+            &DataKey::TrackLicenseTiers(track_id.clone(), LicenseTier::Venue),
+            &LicenseTierConfig {
+                price: base_price,
+                supply: licenses,
+                sold: 0,
+                duration: 0,
+            },
+        );
         env.storage().instance().set(
             &DataKey::ArtistTracks(artist.clone(), track_id.clone()),
             &true,
         );
+
+        Self::list_push(env, &IndexedList::ArtistTracks(artist.clone()), &track_id);
         env.storage()
             .instance()
             .set(&DataKey::TrackIdCounter, &track_counter);
 
+        for tag in tags.iter() {
+            Self::index_track_tag(env, &tag, &track_id, true);
+        }
+
         env.events()
-            .publish((Symbol::new(&env, "track_minted"), track_id.clone()), ());
+            .publish((Symbol::new(env, "track_minted"), track_id.clone()), ());
 
         track_id
     }
 
-    pub fn update_track(
+    pub fn create_campaign(
         env: Env,
         artist: Address,
-        track_id: BytesN<32>,
-        new_base_price: i128,
-        new_licenses: u32,
-        new_metadata_uri: String,
-    ) {
+        draft: TrackDraft,
+        goal: i128,
+        deadline: u64,
+        backer_share_bps: u32,
+    ) -> BytesN<32> {
         artist.require_auth();
+        Self::require_active_user(&env, &artist);
 
-        let mut track: Track = env
+        if !env
             .storage()
             .instance()
-            .get(&DataKey::Tracks(track_id.clone()))
-            .unwrap();
+            .has(&DataKey::Artists(artist.clone()))
+        {
+            panic!("Not registered as artist");
+        }
 
-        if track.artist_id != artist {
-            panic!("Not track owner");
+        Self::check_track_draft(&env, &artist, &draft);
+
+        if backer_share_bps == 0
+            || backer_share_bps >= 10000
+            || !backer_share_bps.is_multiple_of(100)
+        {
+            panic!("Backer share must be whole percent below 100%");
+        }
+        let share_points = (backer_share_bps / 100) as i128;
+        if goal <= 0 || goal % share_points != 0 {
+            panic!("Goal must divide evenly into share points");
+        }
+        if deadline <= env.ledger().timestamp() {
+            panic!("Deadline must be in the future");
         }
 
-        track.base_price = new_base_price;
-        track.licenses_remaining = new_licenses;
-        track.metadata_uri = new_metadata_uri;
+        let mut campaign_counter: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::CampaignIdCounter)
+            .unwrap_or(0);
+        campaign_counter += 1;
+
+        let mut campaign_id_bytes = Bytes::from_slice(&env, b"campaign_");
+        campaign_id_bytes.extend_from_array(&campaign_counter.to_be_bytes());
+        let campaign_id: BytesN<32> = env.crypto().sha256(&campaign_id_bytes).into();
+
+        let campaign = FundingCampaign {
+            campaign_id: campaign_id.clone(),
+            artist: artist.clone(),
+            draft,
+            goal,
+            raised: 0,
+            deadline,
+            backer_share_bps,
+            status: CampaignStatus::Active,
+            track_id: None,
+        };
 
         env.storage()
             .instance()
-            .set(&DataKey::Tracks(track_id), &track);
+            .set(&DataKey::Campaigns(campaign_id.clone()), &campaign);
+        env.storage()
+            .instance()
+            .set(&DataKey::CampaignIdCounter, &campaign_counter);
+
+        env.events().publish(
+            (Symbol::new(&env, "campaign_created"), campaign_id.clone()),
+            artist,
+        );
+
+        campaign_id
     }
 
-    pub fn create_table(
-        env: Env,
-        owner: Address,
-        name: String,
-        skip_threshold: u32,
-        price_multiplier: u32,
-    ) -> BytesN<32> {
-        owner.require_auth();
+    pub fn back_campaign(env: Env, backer: Address, campaign_id: BytesN<32>, amount: i128) {
+        backer.require_auth();
+        Self::require_active_user(&env, &backer);
 
-        if !env.storage().instance().has(&DataKey::Users(owner.clone())) {
-            panic!("User not registered");
+        let mut campaign: FundingCampaign = env
+            .storage()
+            .instance()
+            .get(&DataKey::Campaigns(campaign_id.clone()))
+            .unwrap_or_else(|| panic!("Campaign not found"));
+
+        if campaign.status != CampaignStatus::Active || env.ledger().timestamp() > campaign.deadline
+        {
+            panic!("Campaign not accepting contributions");
+        }
+        if backer == campaign.artist {
+            panic!("Artist cannot back own campaign");
         }
 
-        let mut table_counter: u32 = env
+        let point_price = campaign.goal / (campaign.backer_share_bps / 100) as i128;
+        if amount <= 0 || amount % point_price != 0 {
+            panic!("Contribution must be a multiple of the share price");
+        }
+        if campaign.raised + amount > campaign.goal {
+            panic!("Contribution exceeds remaining goal");
+        }
+
+        let token_address: Address = env
             .storage()
             .instance()
-            .get(&DataKey::TableIdCounter)
+            .get(&DataKey::TokenStellar)
             .unwrap();
-        table_counter += 1;
+        let token_client = token::Client::new(&env, &token_address);
+        token_client.transfer(&backer, &env.current_contract_address(), &amount);
 
-        let table_id_str: String = String::from_str(&env, "table_");
-        let table_id_bytes: BytesN<32> = BytesN::from_val(&env, table_id_str.as_val());
-        let _ = &mut owner
-            .to_string()
-            .copy_into_slice(table_id_bytes.to_array().as_mut());
-        table_id_bytes.copy_into_slice(
-            &mut table_counter
-                .to_be_bytes()
-                .as_mut_slice()
-                .try_into()
-                .unwrap(),
+        let contribution_key = DataKey::CampaignContributions(campaign_id.clone(), backer.clone());
+        let contribution: i128 = env.storage().instance().get(&contribution_key).unwrap_or(0);
+        if contribution == 0 {
+            let mut backers: Vec<Address> = env
+                .storage()
+                .instance()
+                .get(&DataKey::CampaignBackers(campaign_id.clone()))
+                .unwrap_or_else(|| Vec::new(&env));
+            backers.push_back(backer.clone());
+            env.storage()
+                .instance()
+                .set(&DataKey::CampaignBackers(campaign_id.clone()), &backers);
+        }
+
+        campaign.raised += amount;
+
+        env.storage()
+            .instance()
+            .set(&contribution_key, &(contribution + amount));
+        env.storage()
+            .instance()
+            .set(&DataKey::Campaigns(campaign_id.clone()), &campaign);
+
+        env.events().publish(
+            (Symbol::new(&env, "campaign_backed"), campaign_id),
+            (backer, amount),
         );
-        let table_id: BytesN<32> = env.crypto().sha256((&table_id_bytes).as_ref()).into();
+    }
 
-        let new_table = JukeboxTable {
-            table_id: table_id.clone(),
-            name,
-            owner: owner.clone(),
-            current_track: None,
-            queue: Vec::new(&env),
-            skip_votes: Map::new(&env),
-            skip_threshold,
-            price_multiplier,
-            member_count: 0,
-            is_active: true,
+    /// Mints the drafted track once the goal is fully raised, scaling the
+    /// artist's split down to make room for the backers and paying the raised
+    /// funds out to the artist. Anyone may activate a funded campaign; if it is
+    /// still inactive `CAMPAIGN_ACTIVATION_GRACE` after the deadline, backers may
+    /// claim refunds instead.
+    pub fn activate_campaign(env: Env, campaign_id: BytesN<32>) -> BytesN<32> {
+        let mut campaign: FundingCampaign = env
+            .storage()
+            .instance()
+            .get(&DataKey::Campaigns(campaign_id.clone()))
+            .unwrap_or_else(|| panic!("Campaign not found"));
+        let artist = campaign.artist.clone();
+
+        if campaign.status != CampaignStatus::Active {
+            panic!("Campaign not active");
+        }
+        if campaign.raised < campaign.goal {
+            panic!("Funding goal not met");
+        }
+
+        Self::check_track_draft(&env, &artist, &campaign.draft);
+
+        let share_points = campaign.backer_share_bps / 100;
+        let point_price = campaign.goal / share_points as i128;
+
+        let mut royalty_split: Vec<(Address, u32)> = Vec::new(&env);
+        let mut allocated = 0;
+        for (recipient, percentage) in campaign.draft.royalty_split.iter() {
+            let scaled = percentage * (100 - share_points) / 100;
+            allocated += scaled;
+            royalty_split.push_back((recipient, scaled));
+        }
+        let (recipient, percentage) = royalty_split.get(0).unwrap();
+        royalty_split.set(
+            0,
+            (recipient, percentage + (100 - share_points - allocated)),
+        );
+
+        let backers: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&DataKey::CampaignBackers(campaign_id.clone()))
+            .unwrap_or_else(|| Vec::new(&env));
+        for backer in backers.iter() {
+            let contribution: i128 = env
+                .storage()
+                .instance()
+                .get(&DataKey::CampaignContributions(
+                    campaign_id.clone(),
+                    backer.clone(),
+                ))
+                .unwrap();
+            let points = (contribution / point_price) as u32;
+
+            match royalty_split
+                .iter()
+                .position(|(recipient, _)| recipient == backer)
+            {
+                Some(index) => {
+                    let (recipient, percentage) = royalty_split.get(index as u32).unwrap();
+                    royalty_split.set(index as u32, (recipient, percentage + points));
+                }
+                None => royalty_split.push_back((backer, points)),
+            }
+        }
+
+        let mut draft = campaign.draft.clone();
+        draft.royalty_split = Vec::new(&env);
+        for (recipient, percentage) in royalty_split.iter() {
+            if percentage > 0 {
+                draft.royalty_split.push_back((recipient, percentage));
+            }
+        }
+
+        let track_id = Self::create_track(&env, &artist, draft);
+
+        campaign.status = CampaignStatus::Funded;
+        campaign.track_id = Some(track_id.clone());
+        env.storage()
+            .instance()
+            .set(&DataKey::Campaigns(campaign_id.clone()), &campaign);
+
+        let token_address: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::TokenStellar)
+            .unwrap();
+        let token_client = token::Client::new(&env, &token_address);
+        token_client.transfer(&env.current_contract_address(), &artist, &campaign.raised);
+
+        env.events().publish(
+            (Symbol::new(&env, "campaign_funded"), campaign_id),
+            track_id.clone(),
+        );
+
+        track_id
+    }
+
+    pub fn claim_campaign_refund(env: Env, backer: Address, campaign_id: BytesN<32>) -> i128 {
+        backer.require_auth();
+
+        let mut campaign: FundingCampaign = env
+            .storage()
+            .instance()
+            .get(&DataKey::Campaigns(campaign_id.clone()))
+            .unwrap_or_else(|| panic!("Campaign not found"));
+
+        let now = env.ledger().timestamp();
+        let refundable = match campaign.status {
+            CampaignStatus::Funded => false,
+            CampaignStatus::Failed => true,
+            CampaignStatus::Active => {
+                now > campaign.deadline
+                    && (campaign.raised < campaign.goal
+                        || now > campaign.deadline + CAMPAIGN_ACTIVATION_GRACE)
+            }
+        };
+        if !refundable {
+            panic!("Campaign not refundable");
+        }
+
+        let contribution_key = DataKey::CampaignContributions(campaign_id.clone(), backer.clone());
+        let contribution: i128 = env.storage().instance().get(&contribution_key).unwrap_or(0);
+        if contribution == 0 {
+            panic!("Nothing to refund");
+        }
+
+        env.storage().instance().remove(&contribution_key);
+        campaign.status = CampaignStatus::Failed;
+        env.storage()
+            .instance()
+            .set(&DataKey::Campaigns(campaign_id.clone()), &campaign);
+
+        let token_address: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::TokenStellar)
+            .unwrap();
+        let token_client = token::Client::new(&env, &token_address);
+        token_client.transfer(&env.current_contract_address(), &backer, &contribution);
+
+        env.events().publish(
+            (Symbol::new(&env, "campaign_refunded"), campaign_id),
+            (backer, contribution),
+        );
+
+        contribution
+    }
+
+    pub fn get_campaign(env: Env, campaign_id: BytesN<32>) -> Option<FundingCampaign> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Campaigns(campaign_id))
+    }
+
+    pub fn get_campaign_contribution(env: Env, campaign_id: BytesN<32>, backer: Address) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::CampaignContributions(campaign_id, backer))
+            .unwrap_or(0)
+    }
+
+    pub fn update_track(
+        env: Env,
+        artist: Address,
+        track_id: BytesN<32>,
+        new_base_price: i128,
+        new_licenses: u32,
+        new_metadata_uri: String,
+        new_metadata_hash: BytesN<32>,
+        new_tags: Vec<Symbol>,
+    ) {
+        artist.require_auth();
+
+        let mut track: Track = env
+            .storage()
+            .instance()
+            .get(&DataKey::Tracks(track_id.clone()))
+            .unwrap();
+
+        if track.artist_id != artist {
+            panic!("Not track owner");
+        }
+
+        let mut venue_tier = Self::license_tier(&env, &track_id, LicenseTier::Venue);
+        if new_licenses < venue_tier.sold {
+            panic!("Supply below licenses sold");
+        }
+        venue_tier.supply = new_licenses;
+
+        Self::check_track_tags(&env, &new_tags);
+        Self::check_price_cap(&env, &artist, new_base_price);
+        for tag in track.tags.iter() {
+            if !new_tags.contains(&tag) {
+                Self::index_track_tag(&env, &tag, &track_id, false);
+            }
+        }
+        for tag in new_tags.iter() {
+            if !track.tags.contains(&tag) {
+                Self::index_track_tag(&env, &tag, &track_id, true);
+            }
+        }
+
+        track.base_price = new_base_price;
+        track.licenses_remaining = new_licenses - venue_tier.sold;
+        track.tags = new_tags;
+
+        let current_version: MetadataVersion = env
+            .storage()
+            .instance()
+            .get(&DataKey::TrackMetadataVersions(
+                track_id.clone(),
+                track.metadata_version,
+            ))
+            .unwrap();
+        if current_version.uri != new_metadata_uri
+            || current_version.content_hash != new_metadata_hash
+        {
+            track.metadata_version += 1;
+            track.metadata_uri = new_metadata_uri.clone();
+
+            env.storage().instance().set(
+                &DataKey::TrackMetadataVersions(track_id.clone(), track.metadata_version),
+                &MetadataVersion {
+                    version: track.metadata_version,
+                    uri: new_metadata_uri,
+                    content_hash: new_metadata_hash,
+                    created_at: env.ledger().timestamp(),
+                },
+            );
+
+            env.events().publish(
+                (Symbol::new(&env, "metadata_updated"), track_id.clone()),
+                track.metadata_version,
+            );
+        }
+
+        env.storage().instance().set(
+            &DataKey::TrackLicenseTiers(track_id.clone(), LicenseTier::Venue),
+            &venue_tier,
+        );
+        env.storage()
+            .instance()
+            .set(&DataKey::Tracks(track_id), &track);
+    }
+
+    pub fn set_license_tier(
+        env: Env,
+        artist: Address,
+        track_id: BytesN<32>,
+        tier: LicenseTier,
+        price: i128,
+        supply: u32,
+        duration: u64,
+    ) {
+        artist.require_auth();
+
+        let mut track: Track = env
+            .storage()
+            .instance()
+            .get(&DataKey::Tracks(track_id.clone()))
+            .unwrap_or_else(|| panic!("Track not found"));
+
+        if track.artist_id != artist {
+            panic!("Not track owner");
+        }
+
+        if price < 0 {
+            panic!("Invalid license price");
+        }
+
+        let sold = env
+            .storage()
+            .instance()
+            .get::<_, LicenseTierConfig>(&DataKey::TrackLicenseTiers(track_id.clone(), tier))
+            .map(|config| config.sold)
+            .unwrap_or(0);
+        if supply < sold {
+            panic!("Supply below licenses sold");
+        }
+
+        if tier == LicenseTier::Venue {
+            track.licenses_remaining = supply - sold;
+            env.storage()
+                .instance()
+                .set(&DataKey::Tracks(track_id.clone()), &track);
+        }
+
+        env.storage().instance().set(
+            &DataKey::TrackLicenseTiers(track_id, tier),
+            &LicenseTierConfig {
+                price,
+                supply,
+                sold,
+                duration,
+            },
+        );
+    }
+
+    pub fn purchase_license(
+        env: Env,
+        buyer: Address,
+        track_id: BytesN<32>,
+        tier: LicenseTier,
+        table_id: Option<BytesN<32>>,
+    ) -> BytesN<32> {
+        buyer.require_auth();
+        Self::require_active_user(&env, &buyer);
+
+        let mut track: Track = env
+            .storage()
+            .instance()
+            .get(&DataKey::Tracks(track_id.clone()))
+            .unwrap_or_else(|| panic!("Track not found"));
+
+        if Self::is_taken_down(&env, &track_id) {
+            panic!("Track is taken down");
+        }
+
+        let mut tier_config: LicenseTierConfig = env
+            .storage()
+            .instance()
+            .get(&DataKey::TrackLicenseTiers(track_id.clone(), tier))
+            .unwrap_or_else(|| panic!("License tier not offered"));
+
+        if tier_config.sold >= tier_config.supply {
+            panic!("No licenses remaining");
+        }
+
+        let index_key = if tier == LicenseTier::Venue {
+            let table_id = table_id
+                .clone()
+                .unwrap_or_else(|| panic!("Venue license requires a table"));
+            let table: JukeboxTable = env
+                .storage()
+                .instance()
+                .get(&DataKey::Tables(table_id.clone()))
+                .unwrap_or_else(|| panic!("Table not found"));
+
+            if table.owner != buyer {
+                panic!("Not table owner");
+            }
+
+            DataKey::TableLicenses(table_id, track_id.clone())
+        } else {
+            if table_id.is_some() {
+                panic!("Only venue licenses attach to a table");
+            }
+            DataKey::HolderLicenses(buyer.clone(), track_id.clone(), tier)
+        };
+
+        if let Some(existing_id) = env.storage().instance().get::<_, BytesN<32>>(&index_key) {
+            if Self::license_valid(&env, &existing_id) {
+                panic!("License already held");
+            }
+        }
+
+        if tier_config.price > 0 {
+            let token_address: Address = env
+                .storage()
+                .instance()
+                .get(&DataKey::TokenStellar)
+                .unwrap();
+            let token_client = token::Client::new(&env, &token_address);
+            token_client.transfer(&buyer, &env.current_contract_address(), &tier_config.price);
+            Self::distribute_royalties(&env, &track, &tier_config.price, None);
+        }
+
+        let mut license_counter: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::LicenseIdCounter)
+            .unwrap_or(0);
+        license_counter += 1;
+
+        let mut license_id_bytes = Bytes::from_slice(&env, b"license_");
+        license_id_bytes.extend_from_array(&license_counter.to_be_bytes());
+        let license_id: BytesN<32> = env.crypto().sha256(&license_id_bytes).into();
+
+        let purchased_at = env.ledger().timestamp();
+        let license = License {
+            license_id: license_id.clone(),
+            track_id: track_id.clone(),
+            tier,
+            holder: buyer,
+            table_id,
+            purchased_at,
+            expires_at: if tier_config.duration > 0 {
+                Some(purchased_at + tier_config.duration)
+            } else {
+                None
+            },
         };
 
-        env.storage()
-            .instance()
-            .set(&DataKey::Tables(table_id.clone()), &new_table);
-        env.storage()
+        tier_config.sold += 1;
+        if tier == LicenseTier::Venue {
+            track.licenses_remaining = tier_config.supply - tier_config.sold;
+            env.storage()
+                .instance()
+                .set(&DataKey::Tracks(track_id.clone()), &track);
+        }
+
+        env.storage().instance().set(
+            &DataKey::TrackLicenseTiers(track_id.clone(), tier),
+            &tier_config,
+        );
+        env.storage()
+            .instance()
+            .set(&DataKey::Licenses(license_id.clone()), &license);
+        env.storage().instance().set(&index_key, &license_id);
+        if let Some(table_id) = &license.table_id {
+            Self::list_push(
+                &env,
+                &IndexedList::TableLicenses(table_id.clone()),
+                &track_id,
+            );
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::LicenseIdCounter, &license_counter);
+
+        env.events().publish(
+            (Symbol::new(&env, "license_purchased"), license_id.clone()),
+            (track_id, tier),
+        );
+
+        license_id
+    }
+
+    /// Caps the markup above `base_price` that tables may charge for the track.
+    /// Requests at tables with a higher `price_multiplier` are charged the capped
+    /// price instead.
+    pub fn set_venue_markup_cap(
+        env: Env,
+        artist: Address,
+        track_id: BytesN<32>,
+        max_markup_bps: Option<u32>,
+    ) {
+        artist.require_auth();
+
+        let mut track: Track = env
+            .storage()
+            .instance()
+            .get(&DataKey::Tracks(track_id.clone()))
+            .unwrap_or_else(|| panic!("Track not found"));
+
+        if track.artist_id != artist {
+            panic!("Not track owner");
+        }
+
+        track.max_venue_markup_bps = max_markup_bps;
+
+        env.storage()
+            .instance()
+            .set(&DataKey::Tracks(track_id), &track);
+    }
+
+    pub fn set_resale_royalty(env: Env, artist: Address, track_id: BytesN<32>, royalty_bps: u32) {
+        artist.require_auth();
+
+        let mut track: Track = env
+            .storage()
+            .instance()
+            .get(&DataKey::Tracks(track_id.clone()))
+            .unwrap_or_else(|| panic!("Track not found"));
+
+        if track.artist_id != artist {
+            panic!("Not track owner");
+        }
+
+        // Checked against the highest allowed fee so a later fee increase can't
+        // leave the seller with a negative share.
+        if royalty_bps + MAX_PLATFORM_FEE > 10000 {
+            panic!("Resale royalty too high");
+        }
+
+        track.resale_royalty_bps = royalty_bps;
+        env.storage()
+            .instance()
+            .set(&DataKey::Tracks(track_id), &track);
+    }
+
+    pub fn list_license(env: Env, seller: Address, license_id: BytesN<32>, price: i128) {
+        seller.require_auth();
+        Self::require_active_user(&env, &seller);
+
+        let license: License = env
+            .storage()
+            .instance()
+            .get(&DataKey::Licenses(license_id.clone()))
+            .unwrap_or_else(|| panic!("License not found"));
+
+        if Self::license_controller(&env, &license) != seller {
+            panic!("Not license holder");
+        }
+
+        if !Self::license_valid(&env, &license_id) {
+            panic!("License expired");
+        }
+
+        if price <= 0 {
+            panic!("Invalid listing price");
+        }
+
+        if env
+            .storage()
+            .instance()
+            .has(&DataKey::LicenseListings(license_id.clone()))
+        {
+            panic!("License already listed");
+        }
+
+        let listing = LicenseListing {
+            license_id: license_id.clone(),
+            seller: seller.clone(),
+            price,
+            listed_at: env.ledger().timestamp(),
+        };
+
+        env.storage()
+            .instance()
+            .set(&DataKey::LicenseListings(license_id.clone()), &listing);
+
+        env.events().publish(
+            (Symbol::new(&env, "license_listed"), license_id),
+            (seller, price),
+        );
+    }
+
+    pub fn cancel_license_listing(env: Env, seller: Address, license_id: BytesN<32>) {
+        seller.require_auth();
+
+        let listing: LicenseListing = env
+            .storage()
+            .instance()
+            .get(&DataKey::LicenseListings(license_id.clone()))
+            .unwrap_or_else(|| panic!("License not listed"));
+
+        if listing.seller != seller {
+            panic!("Not listing seller");
+        }
+
+        env.storage()
+            .instance()
+            .remove(&DataKey::LicenseListings(license_id));
+    }
+
+    pub fn buy_license(
+        env: Env,
+        buyer: Address,
+        license_id: BytesN<32>,
+        table_id: Option<BytesN<32>>,
+    ) {
+        buyer.require_auth();
+        Self::require_active_user(&env, &buyer);
+
+        let listing: LicenseListing = env
+            .storage()
+            .instance()
+            .get(&DataKey::LicenseListings(license_id.clone()))
+            .unwrap_or_else(|| panic!("License not listed"));
+
+        let mut license: License = env
+            .storage()
+            .instance()
+            .get(&DataKey::Licenses(license_id.clone()))
+            .unwrap();
+
+        if Self::license_controller(&env, &license) != listing.seller {
+            panic!("Listing is stale");
+        }
+
+        if Self::is_taken_down(&env, &license.track_id) {
+            panic!("Track is taken down");
+        }
+
+        if !Self::license_valid(&env, &license_id) {
+            panic!("License expired");
+        }
+
+        if buyer == listing.seller {
+            panic!("Cannot buy own listing");
+        }
+
+        let old_index_key = match license.table_id.clone() {
+            Some(old_table_id) => DataKey::TableLicenses(old_table_id, license.track_id.clone()),
+            None => DataKey::HolderLicenses(
+                license.holder.clone(),
+                license.track_id.clone(),
+                license.tier,
+            ),
+        };
+
+        let new_index_key = if license.tier == LicenseTier::Venue {
+            let table_id = table_id
+                .clone()
+                .unwrap_or_else(|| panic!("Venue license requires a table"));
+            let table: JukeboxTable = env
+                .storage()
+                .instance()
+                .get(&DataKey::Tables(table_id.clone()))
+                .unwrap_or_else(|| panic!("Table not found"));
+
+            if table.owner != buyer {
+                panic!("Not table owner");
+            }
+
+            DataKey::TableLicenses(table_id, license.track_id.clone())
+        } else {
+            if table_id.is_some() {
+                panic!("Only venue licenses attach to a table");
+            }
+            DataKey::HolderLicenses(buyer.clone(), license.track_id.clone(), license.tier)
+        };
+
+        if let Some(existing_id) = env
+            .storage()
+            .instance()
+            .get::<_, BytesN<32>>(&new_index_key)
+        {
+            if Self::license_valid(&env, &existing_id) {
+                panic!("License already held");
+            }
+        }
+
+        let track: Track = env
+            .storage()
+            .instance()
+            .get(&DataKey::Tracks(license.track_id.clone()))
+            .unwrap();
+
+        let platform_fee: u32 = env.storage().instance().get(&DataKey::PlatformFee).unwrap();
+        let fee_amount = (listing.price * platform_fee as i128) / 10000;
+        let royalty_amount = (listing.price * track.resale_royalty_bps as i128) / 10000;
+        let seller_amount = listing.price - fee_amount - royalty_amount;
+
+        let token_address: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::TokenStellar)
+            .unwrap();
+        let token_client = token::Client::new(&env, &token_address);
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+
+        token_client.transfer(&buyer, &env.current_contract_address(), &listing.price);
+        token_client.transfer(&env.current_contract_address(), &admin, &fee_amount);
+        Self::split_royalties(&env, &track, &royalty_amount, None);
+        token_client.transfer(
+            &env.current_contract_address(),
+            &listing.seller,
+            &seller_amount,
+        );
+
+        if let Some(old_table_id) = &license.table_id {
+            Self::list_remove(
+                &env,
+                &IndexedList::TableLicenses(old_table_id.clone()),
+                &license.track_id,
+            );
+        }
+        if let Some(new_table_id) = &table_id {
+            Self::list_push(
+                &env,
+                &IndexedList::TableLicenses(new_table_id.clone()),
+                &license.track_id,
+            );
+        }
+        license.holder = buyer.clone();
+        license.table_id = table_id;
+
+        env.storage().instance().remove(&old_index_key);
+        env.storage().instance().set(&new_index_key, &license_id);
+        env.storage()
+            .instance()
+            .set(&DataKey::Licenses(license_id.clone()), &license);
+        env.storage()
+            .instance()
+            .remove(&DataKey::LicenseListings(license_id.clone()));
+
+        env.events().publish(
+            (Symbol::new(&env, "license_sold"), license_id),
+            (listing.seller, buyer, listing.price),
+        );
+    }
+
+    pub fn get_license_listing(env: Env, license_id: BytesN<32>) -> Option<LicenseListing> {
+        env.storage()
+            .instance()
+            .get(&DataKey::LicenseListings(license_id))
+    }
+
+    pub fn get_license_tier(
+        env: Env,
+        track_id: BytesN<32>,
+        tier: LicenseTier,
+    ) -> Option<LicenseTierConfig> {
+        env.storage()
+            .instance()
+            .get(&DataKey::TrackLicenseTiers(track_id, tier))
+    }
+
+    pub fn get_license(env: Env, license_id: BytesN<32>) -> Option<License> {
+        env.storage().instance().get(&DataKey::Licenses(license_id))
+    }
+
+    pub fn get_table_license(
+        env: Env,
+        table_id: BytesN<32>,
+        track_id: BytesN<32>,
+    ) -> Option<License> {
+        let license_id: BytesN<32> = env
+            .storage()
+            .instance()
+            .get(&DataKey::TableLicenses(table_id, track_id))?;
+        env.storage().instance().get(&DataKey::Licenses(license_id))
+    }
+
+    pub fn has_venue_license(env: Env, table_id: BytesN<32>, track_id: BytesN<32>) -> bool {
+        Self::table_licensed(&env, &table_id, &track_id)
+    }
+
+    pub fn create_table(
+        env: Env,
+        owner: Address,
+        name: String,
+        skip_threshold: u32,
+        price_multiplier: u32,
+    ) -> BytesN<32> {
+        owner.require_auth();
+        Self::require_active_user(&env, &owner);
+
+        let mut table_counter: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::TableIdCounter)
+            .unwrap();
+        table_counter += 1;
+
+        let table_id_str: String = String::from_str(&env, "table_");
+        let table_id_bytes: BytesN<32> = BytesN::from_val(&env, table_id_str.as_val());
+        let _ = &mut owner
+            .to_string()
+            .copy_into_slice(table_id_bytes.to_array().as_mut());
+        table_id_bytes.copy_into_slice(
+            &mut table_counter
+                .to_be_bytes()
+                .as_mut_slice()
+                .try_into()
+                .unwrap(),
+        );
+        let table_id: BytesN<32> = env.crypto().sha256((&table_id_bytes).as_ref()).into();
+
+        let new_table = JukeboxTable {
+            table_id: table_id.clone(),
+            name,
+            owner: owner.clone(),
+            current_track: None,
+            queue: Vec::new(&env),
+            skip_votes: Map::new(&env),
+            skip_threshold,
+            price_multiplier,
+            member_count: 0,
+            is_active: true,
+            current_request: None,
+            pending_requests: Vec::new(&env),
+            min_reputation: 0,
+            reputation_weighted_skips: false,
+            visibility: TableVisibility::Public,
+            max_members: 0,
+            max_queue_len: 0,
+            max_pending_per_user: 0,
+            owner_share_bps: None,
+            total_requests: 0,
+            total_plays: 0,
+            total_revenue: 0,
+        };
+
+        env.storage()
+            .instance()
+            .set(&DataKey::Tables(table_id.clone()), &new_table);
+        env.storage()
+            .instance()
+            .set(&DataKey::TableIdCounter, &table_counter);
+
+        env.events()
+            .publish((Symbol::new(&env, "table_created"), table_id.clone()), ());
+
+        table_id
+    }
+
+    pub fn update_table(
+        env: Env,
+        owner: Address,
+        table_id: BytesN<32>,
+        name: String,
+        skip_threshold: u32,
+        price_multiplier: u32,
+    ) {
+        owner.require_auth();
+
+        let mut table: JukeboxTable = env
+            .storage()
+            .instance()
+            .get(&DataKey::Tables(table_id.clone()))
+            .unwrap();
+
+        if table.owner != owner {
+            panic!("Not table owner");
+        }
+
+        table.name = name;
+        table.skip_threshold = skip_threshold;
+        table.price_multiplier = price_multiplier;
+
+        env.storage()
+            .instance()
+            .set(&DataKey::Tables(table_id), &table);
+    }
+
+    pub fn set_table_tag_rules(
+        env: Env,
+        owner: Address,
+        table_id: BytesN<32>,
+        allowed_genres: Vec<Symbol>,
+        allow_explicit: bool,
+    ) {
+        owner.require_auth();
+
+        let table: JukeboxTable = env
+            .storage()
+            .instance()
+            .get(&DataKey::Tables(table_id.clone()))
+            .unwrap();
+
+        if table.owner != owner {
+            panic!("Not table owner");
+        }
+
+        for genre in allowed_genres.iter() {
+            let tag_info: Tag = env
+                .storage()
+                .instance()
+                .get(&DataKey::Tags(genre))
+                .unwrap_or_else(|| panic!("Unknown tag"));
+            if tag_info.kind != TagKind::Genre {
+                panic!("Not a genre tag");
+            }
+        }
+
+        env.storage().instance().set(
+            &DataKey::TableTagRules(table_id),
+            &TableTagRules {
+                allowed_genres,
+                allow_explicit,
+            },
+        );
+    }
+
+    pub fn get_table_tag_rules(env: Env, table_id: BytesN<32>) -> Option<TableTagRules> {
+        env.storage()
+            .instance()
+            .get(&DataKey::TableTagRules(table_id))
+    }
+
+    pub fn set_table_limits(
+        env: Env,
+        owner: Address,
+        table_id: BytesN<32>,
+        max_members: u32,
+        max_queue_len: u32,
+        max_pending_per_user: u32,
+    ) {
+        owner.require_auth();
+
+        let mut table: JukeboxTable = env
+            .storage()
+            .instance()
+            .get(&DataKey::Tables(table_id.clone()))
+            .unwrap();
+
+        if table.owner != owner {
+            panic!("Not table owner");
+        }
+
+        if max_queue_len > MAX_QUEUE_LEN {
+            panic!("Queue limit above platform maximum");
+        }
+
+        table.max_members = max_members;
+        table.max_queue_len = max_queue_len;
+        table.max_pending_per_user = max_pending_per_user;
+
+        env.storage()
+            .instance()
+            .set(&DataKey::Tables(table_id), &table);
+    }
+
+    /// Sets how the table owner is paid out of each request. `None` credits the
+    /// owner with the premium paid above the track's `base_price`; `Some(bps)`
+    /// credits that share of every payment instead.
+    pub fn set_table_owner_share(
+        env: Env,
+        owner: Address,
+        table_id: BytesN<32>,
+        share_bps: Option<u32>,
+    ) {
+        owner.require_auth();
+
+        let mut table: JukeboxTable = env
+            .storage()
+            .instance()
+            .get(&DataKey::Tables(table_id.clone()))
+            .unwrap();
+
+        if table.owner != owner {
+            panic!("Not table owner");
+        }
+
+        if let Some(share_bps) = share_bps {
+            let platform_fee: u32 = env.storage().instance().get(&DataKey::PlatformFee).unwrap();
+            if share_bps + platform_fee > 10000 {
+                panic!("Owner share too high");
+            }
+        }
+
+        table.owner_share_bps = share_bps;
+
+        env.storage()
+            .instance()
+            .set(&DataKey::Tables(table_id), &table);
+    }
+
+    pub fn get_owner_balance(env: Env, owner: Address) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::OwnerBalances(owner))
+            .unwrap_or(0)
+    }
+
+    pub fn withdraw_owner_revenue(env: Env, owner: Address) -> i128 {
+        owner.require_auth();
+
+        let amount: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::OwnerBalances(owner.clone()))
+            .unwrap_or(0);
+        if amount == 0 {
+            panic!("No revenue to withdraw");
+        }
+
+        env.storage()
+            .instance()
+            .remove(&DataKey::OwnerBalances(owner.clone()));
+
+        let token_address: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::TokenStellar)
+            .unwrap();
+        let token_client = token::Client::new(&env, &token_address);
+
+        token_client.transfer(&env.current_contract_address(), &owner, &amount);
+
+        amount
+    }
+
+    pub fn get_pending_request_count(env: Env, table_id: BytesN<32>, user: Address) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::PendingRequestCount(table_id, user))
+            .unwrap_or(0)
+    }
+
+    pub fn set_table_reputation_rules(
+        env: Env,
+        owner: Address,
+        table_id: BytesN<32>,
+        min_reputation: u32,
+        reputation_weighted_skips: bool,
+    ) {
+        owner.require_auth();
+
+        let mut table: JukeboxTable = env
+            .storage()
+            .instance()
+            .get(&DataKey::Tables(table_id.clone()))
+            .unwrap();
+
+        if table.owner != owner {
+            panic!("Not table owner");
+        }
+
+        table.min_reputation = min_reputation;
+        table.reputation_weighted_skips = reputation_weighted_skips;
+
+        env.storage()
+            .instance()
+            .set(&DataKey::Tables(table_id), &table);
+    }
+
+    pub fn request_track(
+        env: Env,
+        requester: Address,
+        track_id: BytesN<32>,
+        table_id: BytesN<32>,
+        redeem_points: i128,
+    ) -> BytesN<32> {
+        requester.require_auth();
+        let mut table = Self::check_requester(&env, &requester, &table_id);
+
+        let track: Track = env
+            .storage()
+            .instance()
+            .get(&DataKey::Tracks(track_id))
+            .unwrap_or_else(|| panic!("Track not found"));
+
+        Self::check_track_requestable(&env, &table_id, &track, true);
+
+        let price = Self::quote_request(&table, &track);
+        let discount = Self::redeem_loyalty_points(&env, &requester, redeem_points, price);
+        let amount_paid = price - discount;
+
+        let token_address: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::TokenStellar)
+            .unwrap();
+        let token_client = token::Client::new(&env, &token_address);
+        token_client.transfer(&requester, &env.current_contract_address(), &amount_paid);
+
+        let request_id = Self::queue_request(
+            &env,
+            &mut table,
+            &table_id,
+            &track,
+            &requester,
+            amount_paid,
+            redeem_points,
+        );
+
+        env.storage()
+            .instance()
+            .set(&DataKey::Tables(table_id), &table);
+
+        request_id
+    }
+
+    pub fn create_playlist(
+        env: Env,
+        owner: Address,
+        name: String,
+        tracks: Vec<BytesN<32>>,
+        is_public: bool,
+    ) -> BytesN<32> {
+        owner.require_auth();
+        Self::require_active_user(&env, &owner);
+        Self::check_playlist_tracks(&env, &tracks);
+        if Self::list_len(&env, &IndexedList::UserPlaylists(owner.clone())) >= MAX_USER_PLAYLISTS {
+            panic!("Too many playlists");
+        }
+
+        let mut playlist_counter: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::PlaylistIdCounter)
+            .unwrap_or(0);
+        playlist_counter += 1;
+
+        let mut playlist_id_bytes = Bytes::from_slice(&env, b"playlist_");
+        playlist_id_bytes.extend_from_array(&playlist_counter.to_be_bytes());
+        let playlist_id: BytesN<32> = env.crypto().sha256(&playlist_id_bytes).into();
+
+        let playlist = Playlist {
+            playlist_id: playlist_id.clone(),
+            owner: owner.clone(),
+            name,
+            tracks,
+            is_public,
+        };
+
+        env.storage()
+            .instance()
+            .set(&DataKey::Playlists(playlist_id.clone()), &playlist);
+        Self::list_push(&env, &IndexedList::UserPlaylists(owner), &playlist_id);
+        env.storage()
+            .instance()
+            .set(&DataKey::PlaylistIdCounter, &playlist_counter);
+
+        playlist_id
+    }
+
+    pub fn update_playlist(
+        env: Env,
+        owner: Address,
+        playlist_id: BytesN<32>,
+        name: String,
+        tracks: Vec<BytesN<32>>,
+        is_public: bool,
+    ) {
+        owner.require_auth();
+
+        let mut playlist: Playlist = env
+            .storage()
+            .instance()
+            .get(&DataKey::Playlists(playlist_id.clone()))
+            .unwrap_or_else(|| panic!("Playlist not found"));
+
+        if playlist.owner != owner {
+            panic!("Not playlist owner");
+        }
+
+        Self::check_playlist_tracks(&env, &tracks);
+
+        playlist.name = name;
+        playlist.tracks = tracks;
+        playlist.is_public = is_public;
+
+        env.storage()
+            .instance()
+            .set(&DataKey::Playlists(playlist_id), &playlist);
+    }
+
+    pub fn delete_playlist(env: Env, owner: Address, playlist_id: BytesN<32>) {
+        owner.require_auth();
+
+        let playlist: Playlist = env
+            .storage()
+            .instance()
+            .get(&DataKey::Playlists(playlist_id.clone()))
+            .unwrap_or_else(|| panic!("Playlist not found"));
+
+        if playlist.owner != owner {
+            panic!("Not playlist owner");
+        }
+
+        Self::list_remove(&env, &IndexedList::UserPlaylists(owner), &playlist_id);
+        env.storage()
+            .instance()
+            .remove(&DataKey::Playlists(playlist_id));
+    }
+
+    pub fn request_playlist(
+        env: Env,
+        requester: Address,
+        table_id: BytesN<32>,
+        playlist_id: BytesN<32>,
+        max_total: i128,
+    ) -> Vec<BytesN<32>> {
+        requester.require_auth();
+        let mut table = Self::check_requester(&env, &requester, &table_id);
+
+        let playlist: Playlist = env
+            .storage()
+            .instance()
+            .get(&DataKey::Playlists(playlist_id))
+            .unwrap_or_else(|| panic!("Playlist not found"));
+
+        if playlist.owner != requester && !playlist.is_public {
+            panic!("Playlist is private");
+        }
+
+        let mut quoted_tracks: Vec<BytesN<32>> = Vec::new(&env);
+        let mut total: i128 = 0;
+        for track_id in playlist.tracks.iter() {
+            let track: Track = match env
+                .storage()
+                .instance()
+                .get(&DataKey::Tracks(track_id.clone()))
+            {
+                Some(track) => track,
+                None => continue,
+            };
+
+            if !Self::check_track_requestable(&env, &table_id, &track, false) {
+                continue;
+            }
+
+            // Limits only tighten as tracks are added, so the rest of the
+            // playlist would be skipped too.
+            if Self::check_queue_limits(&env, &table, &table_id, &requester, quoted_tracks.len())
+                .is_some()
+            {
+                break;
+            }
+
+            total += Self::quote_request(&table, &track);
+            quoted_tracks.push_back(track_id);
+        }
+
+        if quoted_tracks.is_empty() {
+            panic!("No requestable tracks in playlist");
+        }
+
+        if total > max_total {
+            panic!("Playlist total exceeds maximum");
+        }
+
+        let token_address: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::TokenStellar)
+            .unwrap();
+        let token_client = token::Client::new(&env, &token_address);
+        token_client.transfer(&requester, &env.current_contract_address(), &total);
+
+        let mut request_ids = Vec::new(&env);
+        for track_id in quoted_tracks.iter() {
+            let track: Track = env
+                .storage()
+                .instance()
+                .get(&DataKey::Tracks(track_id))
+                .unwrap();
+            let amount_paid = Self::quote_request(&table, &track);
+
+            request_ids.push_back(Self::queue_request(
+                &env,
+                &mut table,
+                &table_id,
+                &track,
+                &requester,
+                amount_paid,
+                0,
+            ));
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::Tables(table_id), &table);
+
+        request_ids
+    }
+
+    pub fn get_playlist(env: Env, playlist_id: BytesN<32>) -> Option<Playlist> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Playlists(playlist_id))
+    }
+
+    pub fn get_user_playlists(
+        env: Env,
+        user: Address,
+        cursor: u32,
+        limit: u32,
+    ) -> (Vec<BytesN<32>>, Option<u32>) {
+        Self::list_page(&env, &IndexedList::UserPlaylists(user), cursor, limit)
+    }
+
+    pub fn set_favorite_track(env: Env, user: Address, track_id: BytesN<32>, favorite: bool) {
+        user.require_auth();
+        Self::require_active_user(&env, &user);
+
+        let favorites = IndexedList::FavoriteTracks(user);
+        if !favorite {
+            Self::list_remove(&env, &favorites, &track_id);
+            return;
+        }
+        if Self::list_contains(&env, &favorites, &track_id) {
+            return;
+        }
+
+        if !env
+            .storage()
+            .instance()
+            .has(&DataKey::Tracks(track_id.clone()))
+        {
+            panic!("Track not found");
+        }
+        if Self::list_len(&env, &favorites) >= MAX_FAVORITES {
+            panic!("Too many favorites");
+        }
+        Self::list_push(&env, &favorites, &track_id);
+    }
+
+    pub fn set_favorite_artist(env: Env, user: Address, artist: Address, favorite: bool) {
+        user.require_auth();
+        Self::require_active_user(&env, &user);
+
+        let favorites = IndexedList::FavoriteArtists(user);
+        if !favorite {
+            Self::list_remove(&env, &favorites, &artist);
+            return;
+        }
+        if Self::list_contains(&env, &favorites, &artist) {
+            return;
+        }
+
+        if !env
+            .storage()
+            .instance()
+            .has(&DataKey::Artists(artist.clone()))
+        {
+            panic!("Artist not found");
+        }
+        if Self::list_len(&env, &favorites) >= MAX_FAVORITES {
+            panic!("Too many favorites");
+        }
+        Self::list_push(&env, &favorites, &artist);
+    }
+
+    pub fn get_favorite_tracks(
+        env: Env,
+        user: Address,
+        cursor: u32,
+        limit: u32,
+    ) -> (Vec<BytesN<32>>, Option<u32>) {
+        Self::list_page(&env, &IndexedList::FavoriteTracks(user), cursor, limit)
+    }
+
+    pub fn get_favorite_artists(
+        env: Env,
+        user: Address,
+        cursor: u32,
+        limit: u32,
+    ) -> (Vec<Address>, Option<u32>) {
+        Self::list_page(&env, &IndexedList::FavoriteArtists(user), cursor, limit)
+    }
+
+    pub fn get_listener_stats(env: Env, user: Address) -> ListenerStats {
+        Self::listener_stats(&env, &user)
+    }
+
+    fn listener_stats(env: &Env, user: &Address) -> ListenerStats {
+        env.storage()
+            .instance()
+            .get(&DataKey::ListenerStats(user.clone()))
+            .unwrap_or(ListenerStats {
+                requests_made: 0,
+                total_spent: 0,
+                skips_cast: 0,
+                plays_completed: 0,
+            })
+    }
+
+    fn check_requester(env: &Env, requester: &Address, table_id: &BytesN<32>) -> JukeboxTable {
+        let user_data = Self::require_active_user(env, requester);
+
+        let table: JukeboxTable = env
+            .storage()
+            .instance()
+            .get(&DataKey::Tables(table_id.clone()))
+            .unwrap_or_else(|| panic!("Table not found"));
+
+        if !table.is_active {
+            panic!("Table is closed");
+        }
+
+        Self::require_table_member(env, table_id, requester);
+
+        if Self::current_reputation(env, &user_data) < table.min_reputation {
+            panic!("Reputation below table minimum");
+        }
+
+        table
+    }
+
+    fn check_track_requestable(
+        env: &Env,
+        table_id: &BytesN<32>,
+        track: &Track,
+        strict: bool,
+    ) -> bool {
+        if Self::is_taken_down(env, &track.track_id) {
+            if strict {
+                panic!("Track taken down");
+            }
+            return false;
+        }
+
+        if Self::is_blocked_on_table(env, table_id, track) {
+            if strict {
+                panic!("Track blocked on this table");
+            }
+            return false;
+        }
+
+        if !Self::table_licensed(env, table_id, &track.track_id) {
+            if strict {
+                panic!("Track not licensed for this table");
+            }
+            return false;
+        }
+
+        if let Some(rules) = env
+            .storage()
+            .instance()
+            .get::<_, TableTagRules>(&DataKey::TableTagRules(table_id.clone()))
+        {
+            let mut explicit = false;
+            let mut genre_allowed = rules.allowed_genres.is_empty();
+            for tag in track.tags.iter() {
+                let tag_info: Tag = match env.storage().instance().get(&DataKey::Tags(tag.clone()))
+                {
+                    Some(tag_info) => tag_info,
+                    None => continue,
+                };
+                match tag_info.kind {
+                    TagKind::Explicit => explicit = true,
+                    TagKind::Genre => {
+                        if rules.allowed_genres.contains(&tag) {
+                            genre_allowed = true;
+                        }
+                    }
+                    TagKind::Mood => {}
+                }
+            }
+
+            if explicit && !rules.allow_explicit {
+                if strict {
+                    panic!("Explicit tracks not allowed on this table");
+                }
+                return false;
+            }
+
+            if !genre_allowed {
+                if strict {
+                    panic!("Genre not allowed on this table");
+                }
+                return false;
+            }
+        }
+
+        true
+    }
+
+    fn check_track_tags(env: &Env, tags: &Vec<Symbol>) {
+        if tags.len() > MAX_TRACK_TAGS {
+            panic!("Too many tags");
+        }
+
+        for (index, tag) in tags.iter().enumerate() {
+            let tag_info: Tag = env
+                .storage()
+                .instance()
+                .get(&DataKey::Tags(tag.clone()))
+                .unwrap_or_else(|| panic!("Unknown tag"));
+
+            if !tag_info.active {
+                panic!("Tag retired");
+            }
+
+            if tags.first_index_of(&tag) != Some(index as u32) {
+                panic!("Duplicate tag");
+            }
+        }
+    }
+
+    fn index_track_tag(env: &Env, tag: &Symbol, track_id: &BytesN<32>, add: bool) {
+        let list = IndexedList::TagTracks(tag.clone());
+        if add {
+            Self::list_push(env, &list, track_id);
+        } else {
+            Self::list_remove(env, &list, track_id);
+        }
+    }
+
+    fn license_tier(env: &Env, track_id: &BytesN<32>, tier: LicenseTier) -> LicenseTierConfig {
+        env.storage()
+            .instance()
+            .get(&DataKey::TrackLicenseTiers(track_id.clone(), tier))
+            .unwrap_or(LicenseTierConfig {
+                price: 0,
+                supply: 0,
+                sold: 0,
+                duration: 0,
+            })
+    }
+
+    fn license_controller(env: &Env, license: &License) -> Address {
+        match license.table_id.clone() {
+            Some(table_id) => env
+                .storage()
+                .instance()
+                .get::<_, JukeboxTable>(&DataKey::Tables(table_id))
+                .map(|table| table.owner)
+                .unwrap_or(license.holder.clone()),
+            None => license.holder.clone(),
+        }
+    }
+
+    fn license_valid(env: &Env, license_id: &BytesN<32>) -> bool {
+        match env
+            .storage()
+            .instance()
+            .get::<_, License>(&DataKey::Licenses(license_id.clone()))
+        {
+            Some(license) => match license.expires_at {
+                Some(expiry) => expiry > env.ledger().timestamp(),
+                None => true,
+            },
+            None => false,
+        }
+    }
+
+    fn table_licensed(env: &Env, table_id: &BytesN<32>, track_id: &BytesN<32>) -> bool {
+        match env
+            .storage()
+            .instance()
+            .get::<_, BytesN<32>>(&DataKey::TableLicenses(table_id.clone(), track_id.clone()))
+        {
+            Some(license_id) => Self::license_valid(env, &license_id),
+            None => false,
+        }
+    }
+
+    fn check_playlist_tracks(env: &Env, tracks: &Vec<BytesN<32>>) {
+        if tracks.len() > MAX_PLAYLIST_TRACKS {
+            panic!("Too many playlist tracks");
+        }
+        for track_id in tracks.iter() {
+            if !env.storage().instance().has(&DataKey::Tracks(track_id)) {
+                panic!("Track not found");
+            }
+        }
+    }
+
+    fn quote_request(table: &JukeboxTable, track: &Track) -> i128 {
+        let multiplier = match track.max_venue_markup_bps {
+            Some(max_markup) => table.price_multiplier.min(10000 + max_markup),
+            None => table.price_multiplier,
+        };
+        (track.base_price * multiplier as i128) / 10000
+    }
+
+    fn venue_cut(env: &Env, table: &JukeboxTable, track: &Track, amount_paid: i128) -> i128 {
+        let platform_fee: u32 = env.storage().instance().get(&DataKey::PlatformFee).unwrap();
+        let fee_amount = (amount_paid * platform_fee as i128) / 10000;
+
+        let cut = match table.owner_share_bps {
+            Some(share_bps) => (amount_paid * share_bps as i128) / 10000,
+            None => amount_paid - track.base_price,
+        };
+        cut.max(0).min(amount_paid - fee_amount)
+    }
+
+    /// Checks whether `requester` can queue one more request on top of
+    /// `planned` requests that are about to be queued in the same call.
+    fn check_queue_limits(
+        env: &Env,
+        table: &JukeboxTable,
+        table_id: &BytesN<32>,
+        requester: &Address,
+        planned: u32,
+    ) -> Option<LimitError> {
+        let queue_limit = match table.max_queue_len {
+            0 => MAX_QUEUE_LEN,
+            limit => limit.min(MAX_QUEUE_LEN),
+        };
+        // With nothing playing, the first planned request starts immediately
+        // instead of joining the queue.
+        let queue_len = match table.current_track {
+            Some(_) => table.queue.len() + planned,
+            None => table.queue.len() + planned.saturating_sub(1),
+        };
+        if (table.current_track.is_some() || planned > 0) && queue_len >= queue_limit {
+            return Some(LimitError::QueueLimitReached);
+        }
+
+        let pending_count: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::PendingRequestCount(
+                table_id.clone(),
+                requester.clone(),
+            ))
+            .unwrap_or(0);
+        if table.max_pending_per_user > 0 && pending_count + planned >= table.max_pending_per_user {
+            return Some(LimitError::PendingLimitReached);
+        }
+
+        None
+    }
+
+    fn queue_request(
+        env: &Env,
+        table: &mut JukeboxTable,
+        table_id: &BytesN<32>,
+        track: &Track,
+        requester: &Address,
+        amount_paid: i128,
+        points_redeemed: i128,
+    ) -> BytesN<32> {
+        if let Some(error) = Self::check_queue_limits(env, table, table_id, requester, 0) {
+            panic_with_error!(env, error);
+        }
+
+        let pending_count: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::PendingRequestCount(
+                table_id.clone(),
+                requester.clone(),
+            ))
+            .unwrap_or(0);
+
+        let mut request_counter: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::RequestIdCounter)
+            .unwrap();
+        request_counter += 1;
+
+        let request_id: BytesN<32> = env
+            .crypto()
+            .sha256(&Bytes::from_array(env, &request_counter.to_be_bytes()))
+            .into();
+
+        env.storage().instance().set(
+            &DataKey::PendingRequestCount(table_id.clone(), requester.clone()),
+            &(pending_count + 1),
+        );
+
+        let mut request = TrackRequest {
+            request_id: request_id.clone(),
+            requester: requester.clone(),
+            track_id: track.track_id.clone(),
+            table_id: table_id.clone(),
+            timestamp: env.ledger().timestamp(),
+            amount_paid,
+            status: RequestStatus::Queued,
+            metadata_version: track.metadata_version,
+            venue_cut: 0,
+            discount: 0,
+            points_redeemed,
+        };
+        if points_redeemed > 0 {
+            let config: LoyaltyConfig = env
+                .storage()
+                .instance()
+                .get(&DataKey::LoyaltyConfig)
+                .unwrap();
+            request.discount = points_redeemed * config.point_value;
+        }
+        request.venue_cut = Self::venue_cut(env, table, track, amount_paid + request.discount);
+
+        if table.current_track.is_none() {
+            Self::distribute_royalties(env, track, &amount_paid, Some(&request));
+            Self::award_request_points(env, &request);
+            Self::record_settlement(env, &track.track_id, requester, &amount_paid);
+            request.status = RequestStatus::Playing;
+            table.current_track = Some(track.track_id.clone());
+            table.current_request = Some(request_id.clone());
+        } else {
+            table.queue.push_back(track.track_id.clone());
+            table.pending_requests.push_back(request_id.clone());
+        }
+
+        let table_requests = IndexedList::TableRequests(table_id.clone());
+        Self::list_push(env, &table_requests, &request_id);
+        table.total_requests += 1;
+        table.total_revenue += amount_paid;
+
+        env.storage()
+            .instance()
+            .set(&DataKey::Requests(request_id.clone()), &request);
+        env.storage()
+            .instance()
+            .set(&DataKey::RequestIdCounter, &request_counter);
+
+        let mut stats = Self::listener_stats(env, requester);
+        stats.requests_made += 1;
+        stats.total_spent += amount_paid;
+        env.storage()
+            .instance()
+            .set(&DataKey::ListenerStats(requester.clone()), &stats);
+
+        Self::bump_leaderboards(
+            env,
+            LeaderboardKind::HighestGrossing,
+            LeaderboardSubject::Track(track.track_id.clone()),
+            amount_paid,
+            request.timestamp,
+            None,
+        );
+        Self::bump_leaderboards(
+            env,
+            LeaderboardKind::BusiestTables,
+            LeaderboardSubject::Table(table_id.clone()),
+            1,
+            request.timestamp,
+            Some(Self::list_len(env, &table_requests) as i128),
+        );
+        Self::bump_leaderboards(
+            env,
+            LeaderboardKind::TopRequesters,
+            LeaderboardSubject::User(requester.clone()),
+            1,
+            request.timestamp,
+            Some(stats.requests_made as i128),
+        );
+
+        env.events().publish(
+            (Symbol::new(env, "track_requested"), request_id.clone()),
+            (),
+        );
+
+        request_id
+    }
+
+    pub fn tip_current(env: Env, tipper: Address, table_id: BytesN<32>, amount: i128) {
+        tipper.require_auth();
+        Self::require_active_user(&env, &tipper);
+
+        if amount <= 0 {
+            panic!("Invalid tip amount");
+        }
+
+        let table: JukeboxTable = env
+            .storage()
+            .instance()
+            .get(&DataKey::Tables(table_id.clone()))
+            .unwrap_or_else(|| panic!("Table not found"));
+
+        Self::require_table_member(&env, &table_id, &tipper);
+
+        let track_id = table
+            .current_track
+            .unwrap_or_else(|| panic!("No track currently playing"));
+        if Self::is_taken_down(&env, &track_id) {
+            panic!("Track is taken down");
+        }
+
+        let track: Track = env
+            .storage()
+            .instance()
+            .get(&DataKey::Tracks(track_id.clone()))
+            .unwrap();
+
+        let token_address: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::TokenStellar)
+            .unwrap();
+        let token_client = token::Client::new(&env, &token_address);
+        token_client.transfer(&tipper, &env.current_contract_address(), &amount);
+
+        let platform_fee: u32 = env.storage().instance().get(&DataKey::PlatformFee).unwrap();
+        let tip_fee: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::TipFee)
+            .unwrap_or(0u32)
+            .min(platform_fee);
+        let fee_amount = (amount * tip_fee as i128) / 10000;
+        if fee_amount > 0 {
+            let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+            token_client.transfer(&env.current_contract_address(), &admin, &fee_amount);
+        }
+
+        Self::split_royalties(&env, &track, &(amount - fee_amount), None);
+
+        let tips_total: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::TrackTips(track_id.clone()))
+            .unwrap_or(0);
+        env.storage().instance().set(
+            &DataKey::TrackTips(track_id.clone()),
+            &(tips_total + amount),
+        );
+
+        env.events().publish(
+            (Symbol::new(&env, "tip_sent"), table_id, track_id),
+            (tipper, amount),
+        );
+    }
+
+    pub fn get_track_tips(env: Env, track_id: BytesN<32>) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TrackTips(track_id))
+            .unwrap_or(0)
+    }
+
+    pub fn vote_to_skip(env: Env, user: Address, table_id: BytesN<32>) -> bool {
+        user.require_auth();
+        Self::require_active_user(&env, &user);
+
+        let mut table: JukeboxTable = env
+            .storage()
+            .instance()
+            .get(&DataKey::Tables(table_id.clone()))
+            .unwrap();
+
+        Self::require_table_member(&env, &table_id, &user);
+
+        if table.current_track.is_none() {
+            panic!("No track currently playing");
+        }
+
+        if !table.skip_votes.get(user.clone()).unwrap_or(false) {
+            let mut stats = Self::listener_stats(&env, &user);
+            stats.skips_cast += 1;
+            env.storage()
+                .instance()
+                .set(&DataKey::ListenerStats(user.clone()), &stats);
+        }
+
+        table.skip_votes.set(user.clone(), true);
+        let should_skip = if table.reputation_weighted_skips {
+            let mut vote_weight: u64 = 0;
+            for (voter, voted) in table.skip_votes.iter() {
+                if !voted {
+                    continue;
+                }
+                if let Some(voter_data) = env
+                    .storage()
+                    .instance()
+                    .get::<_, User>(&DataKey::Users(voter))
+                {
+                    vote_weight += Self::current_reputation(&env, &voter_data) as u64;
+                }
+            }
+            vote_weight >= table.skip_threshold as u64 * BASE_REPUTATION as u64
+        } else {
+            let vote_count = table.skip_votes.values().into_iter().filter(|&v| v).count();
+            vote_count >= table.skip_threshold as usize
+        };
+
+        if should_skip {
+            env.storage()
+                .instance()
+                .set(&DataKey::Tables(table_id.clone()), &table);
+            Self::advance_queue(&env, table_id, true);
+            true
+        } else {
+            env.storage()
+                .instance()
+                .set(&DataKey::Tables(table_id), &table);
+            false
+        }
+    }
+
+    fn advance_queue(env: &Env, table_id: BytesN<32>, skipped: bool) -> Option<BytesN<32>> {
+        let mut table: JukeboxTable = env
+            .storage()
+            .instance()
+            .get(&DataKey::Tables(table_id.clone()))
+            .unwrap();
+
+        if let Some(request_id) = table.current_request.clone() {
+            Self::finish_request(env, &mut table, &request_id, skipped);
+        }
+        table.skip_votes = Map::new(env);
+
+        let next_track = loop {
+            if table.queue.is_empty() {
+                table.current_track = None;
+                table.current_request = None;
+                env.storage()
+                    .instance()
+                    .set(&DataKey::Tables(table_id.clone()), &table);
+                return None;
+            }
+
+            let next_track = table.queue.pop_front().unwrap();
+            table.current_track = Some(next_track.clone());
+            table.current_request = table.pending_requests.pop_front();
+
+            if !Self::is_taken_down(env, &next_track) {
+                break next_track;
+            }
+            if let Some(request_id) = table.current_request.clone() {
+                Self::remove_request(env, &mut table, &request_id);
+            }
+        };
+
+        if let Some(request_id) = table.current_request.clone() {
+            Self::start_request(env, &request_id);
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::Tables(table_id.clone()), &table);
+
+        Some(next_track)
+    }
+
+    fn start_request(env: &Env, request_id: &BytesN<32>) {
+        let mut request: TrackRequest = env
+            .storage()
+            .instance()
+            .get(&DataKey::Requests(request_id.clone()))
+            .unwrap();
+        let track: Track = env
+            .storage()
+            .instance()
+            .get(&DataKey::Tracks(request.track_id.clone()))
+            .unwrap();
+
+        Self::distribute_royalties(env, &track, &request.amount_paid, Some(&request));
+        Self::award_request_points(env, &request);
+        Self::record_settlement(
+            env,
+            &track.track_id,
+            &request.requester,
+            &request.amount_paid,
+        );
+        request.status = RequestStatus::Playing;
+
+        env.storage()
+            .instance()
+            .set(&DataKey::Requests(request_id.clone()), &request);
+    }
+
+    fn finish_request(env: &Env, table: &mut JukeboxTable, request_id: &BytesN<32>, skipped: bool) {
+        let mut request: TrackRequest = env
+            .storage()
+            .instance()
+            .get(&DataKey::Requests(request_id.clone()))
+            .unwrap();
+        let config = Self::reputation_config(env);
+        Self::release_pending_request(env, &request);
+
+        if skipped {
+            request.status = RequestStatus::Skipped;
+            Self::adjust_reputation(env, &request.requester, 0, config.skip_penalty);
+        } else {
+            request.status = RequestStatus::Played;
+            table.total_plays += 1;
+            Self::adjust_reputation(env, &request.requester, config.play_reward, 0);
+
+            let mut stats = Self::listener_stats(env, &request.requester);
+            stats.plays_completed += 1;
+            env.storage()
+                .instance()
+                .set(&DataKey::ListenerStats(request.requester.clone()), &stats);
+
+            if let Some(loyalty) = env
+                .storage()
+                .instance()
+                .get::<_, LoyaltyConfig>(&DataKey::LoyaltyConfig)
+            {
+                Self::credit_loyalty_points(env, &request.requester, loyalty.points_per_play);
+            }
+
+            if let Some(mut track) = env
+                .storage()
+                .instance()
+                .get::<_, Track>(&DataKey::Tracks(request.track_id.clone()))
+            {
+                track.play_count += 1;
+                env.storage()
+                    .instance()
+                    .set(&DataKey::Tracks(request.track_id.clone()), &track);
+
+                Self::bump_leaderboards(
+                    env,
+                    LeaderboardKind::MostPlayed,
+                    LeaderboardSubject::Track(track.track_id.clone()),
+                    1,
+                    env.ledger().timestamp(),
+                    Some(track.play_count as i128),
+                );
+            }
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::Requests(request_id.clone()), &request);
+    }
+
+    fn normalize_artist_name(env: &Env, artist_name: &String) -> Bytes {
+        let len = artist_name.len();
+        if len == 0 || len > MAX_ARTIST_NAME_LEN {
+            panic!("Invalid artist name length");
+        }
+
+        let mut buffer = [0u8; MAX_ARTIST_NAME_LEN as usize];
+        let name_bytes = &mut buffer[..len as usize];
+        artist_name.copy_into_slice(name_bytes);
+        name_bytes.make_ascii_lowercase();
+
+        Bytes::from_slice(env, name_bytes)
+    }
+
+    fn claim_artist_name(env: &Env, artist: &Address, artist_name: &String) {
+        let name = Self::normalize_artist_name(env, artist_name);
+
+        if let Some(reservation) = env
+            .storage()
+            .instance()
+            .get::<_, NameReservation>(&DataKey::ArtistNames(name.clone()))
+        {
+            let released = match reservation.released_at {
+                Some(released_at) => {
+                    released_at + NAME_RELEASE_COOLDOWN <= env.ledger().timestamp()
+                }
+                None => false,
+            };
+            if reservation.artist != *artist && !released {
+                panic!("Artist name taken");
+            }
+        }
+
+        env.storage().instance().set(
+            &DataKey::ArtistNames(name),
+            &NameReservation {
+                artist: artist.clone(),
+                released_at: None,
+            },
+        );
+    }
+
+    fn require_moderator(env: &Env, moderator: &Address) {
+        moderator.require_auth();
+
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if *moderator != admin
+            && !env
+                .storage()
+                .instance()
+                .has(&DataKey::Moderators(moderator.clone()))
+        {
+            panic!("Not a moderator");
+        }
+    }
+
+    fn require_active_user(env: &Env, user: &Address) -> User {
+        let mut user_data: User = env
+            .storage()
+            .instance()
+            .get(&DataKey::Users(user.clone()))
+            .unwrap_or_else(|| panic!("User not registered"));
+
+        if user_data.is_active {
+            return user_data;
+        }
+
+        let suspension: Option<Suspension> = env
+            .storage()
+            .instance()
+            .get(&DataKey::Suspensions(user.clone()));
+        match suspension.and_then(|s| s.expires_at) {
+            Some(expiry) if expiry <= env.ledger().timestamp() => {
+                user_data.is_active = true;
+                env.storage()
+                    .instance()
+                    .set(&DataKey::Users(user.clone()), &user_data);
+                env.storage()
+                    .instance()
+                    .remove(&DataKey::Suspensions(user.clone()));
+                user_data
+            }
+            _ => panic!("User is suspended"),
+        }
+    }
+
+    fn clear_table_queue(env: &Env, table: &mut JukeboxTable) {
+        if let Some(request_id) = table.current_request.clone() {
+            Self::remove_request(env, table, &request_id);
+        }
+        for request_id in table.pending_requests.clone().iter() {
+            Self::remove_request(env, table, &request_id);
+        }
+
+        table.queue = Vec::new(env);
+        table.current_track = None;
+        table.current_request = None;
+        table.pending_requests = Vec::new(env);
+        table.skip_votes = Map::new(env);
+    }
+
+    fn remove_request(env: &Env, table: &mut JukeboxTable, request_id: &BytesN<32>) {
+        let mut request: TrackRequest = env
+            .storage()
+            .instance()
+            .get(&DataKey::Requests(request_id.clone()))
+            .unwrap();
+
+        Self::release_pending_request(env, &request);
+
+        if request.status == RequestStatus::Queued {
+            let token_address: Address = env
+                .storage()
+                .instance()
+                .get(&DataKey::TokenStellar)
+                .unwrap();
+            let token_client = token::Client::new(env, &token_address);
+            token_client.transfer(
+                &env.current_contract_address(),
+                &request.requester,
+                &request.amount_paid,
+            );
+            request.status = RequestStatus::Refunded;
+            table.total_revenue -= request.amount_paid;
+            Self::bump_leaderboards(
+                env,
+                LeaderboardKind::HighestGrossing,
+                LeaderboardSubject::Track(request.track_id.clone()),
+                -request.amount_paid,
+                request.timestamp,
+                None,
+            );
+            Self::credit_loyalty_points(env, &request.requester, request.points_redeemed);
+
+            let mut stats = Self::listener_stats(env, &request.requester);
+            stats.total_spent -= request.amount_paid;
+            env.storage()
+                .instance()
+                .set(&DataKey::ListenerStats(request.requester.clone()), &stats);
+        } else {
+            request.status = RequestStatus::Removed;
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::Requests(request_id.clone()), &request);
+    }
+
+    fn release_pending_request(env: &Env, request: &TrackRequest) {
+        let key = DataKey::PendingRequestCount(request.table_id.clone(), request.requester.clone());
+        let pending_count: u32 = env.storage().instance().get(&key).unwrap_or(0);
+
+        if pending_count <= 1 {
+            env.storage().instance().remove(&key);
+        } else {
+            env.storage().instance().set(&key, &(pending_count - 1));
+        }
+    }
+
+    fn reputation_config(env: &Env) -> ReputationConfig {
+        env.storage()
+            .instance()
+            .get(&DataKey::ReputationConfig)
+            .unwrap_or(ReputationConfig {
+                play_reward: 2,
+                skip_penalty: 5,
+                decay_interval: 86400,
+                decay_step: 1,
+                max_reputation: 1000,
+            })
+    }
+
+    fn current_reputation(env: &Env, user: &User) -> u32 {
+        let config = Self::reputation_config(env);
+        if config.decay_interval == 0 {
+            return user.reputation;
+        }
+
+        let elapsed = env
+            .ledger()
+            .timestamp()
+            .saturating_sub(user.reputation_updated_at);
+        let decay = (elapsed / config.decay_interval)
+            .saturating_mul(config.decay_step as u64)
+            .min(u32::MAX as u64) as u32;
+
+        if user.reputation > BASE_REPUTATION {
+            user.reputation.saturating_sub(decay).max(BASE_REPUTATION)
+        } else {
+            user.reputation.saturating_add(decay).min(BASE_REPUTATION)
+        }
+    }
+
+    fn adjust_reputation(env: &Env, user: &Address, gained: u32, lost: u32) {
+        let mut user_data: User = match env.storage().instance().get(&DataKey::Users(user.clone()))
+        {
+            Some(user_data) => user_data,
+            None => return,
+        };
+        let config = Self::reputation_config(env);
+
+        user_data.reputation = Self::current_reputation(env, &user_data)
+            .saturating_add(gained)
+            .saturating_sub(lost)
+            .min(config.max_reputation);
+        user_data.reputation_updated_at = env.ledger().timestamp();
+
+        env.storage()
+            .instance()
+            .set(&DataKey::Users(user.clone()), &user_data);
+
+        env.events().publish(
+            (Symbol::new(env, "reputation_changed"), user.clone()),
+            user_data.reputation,
+        );
+    }
+
+    fn distribute_royalties(
+        env: &Env,
+        track: &Track,
+        payment_amount: &i128,
+        request: Option<&TrackRequest>,
+    ) {
+        let platform_fee: u32 = env.storage().instance().get(&DataKey::PlatformFee).unwrap();
+
+        // Loyalty discounts are funded out of the platform's share of the
+        // undiscounted price.
+        let discount = request.map_or(0, |request| request.discount);
+        let fee_amount = ((payment_amount + discount) * platform_fee as i128) / 10000;
+        let fee_amount = (fee_amount - discount).max(0);
+        let mut royalty_amount = payment_amount - fee_amount;
+
+        let request_table = request.map(|request| request.table_id.clone());
+        // The cut was sized against the fee at request time; a later fee raise
+        // must not push the royalty share negative.
+        let venue_cut = request
+            .map_or(0, |request| request.venue_cut)
+            .min(royalty_amount);
+
+        if venue_cut > 0 {
+            if let Some(table) = request_table.as_ref().and_then(|table_id| {
+                env.storage()
+                    .instance()
+                    .get::<_, JukeboxTable>(&DataKey::Tables(table_id.clone()))
+            }) {
+                let owner_balance: i128 = env
+                    .storage()
+                    .instance()
+                    .get(&DataKey::OwnerBalances(table.owner.clone()))
+                    .unwrap_or(0);
+                env.storage().instance().set(
+                    &DataKey::OwnerBalances(table.owner),
+                    &(owner_balance + venue_cut),
+                );
+                royalty_amount -= venue_cut;
+            }
+        }
+
+        let token_address: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::TokenStellar)
+            .unwrap();
+        let token_client = token::Client::new(env, &token_address);
+
+        let referral_reward = match request {
+            Some(request) => Self::pay_referral_reward(env, &request.requester, fee_amount),
+            None => 0,
+        };
+
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        token_client.transfer(
+            &env.current_contract_address(),
+            &admin,
+            &(fee_amount - referral_reward),
+        );
+
+        Self::split_royalties(env, track, &royalty_amount, request_table);
+    }
+
+    fn loyalty_account(env: &Env, user: &Address) -> LoyaltyAccount {
+        let account: LoyaltyAccount = env
+            .storage()
+            .instance()
+            .get(&DataKey::LoyaltyAccounts(user.clone()))
+            .unwrap_or(LoyaltyAccount {
+                points: 0,
+                expires_at: 0,
+            });
+
+        if account.expires_at <= env.ledger().timestamp() {
+            LoyaltyAccount {
+                points: 0,
+                expires_at: 0,
+            }
+        } else {
+            account
+        }
+    }
+
+    fn credit_loyalty_points(env: &Env, user: &Address, points: i128) {
+        if points <= 0 {
+            return;
+        }
+        let config: LoyaltyConfig = match env.storage().instance().get(&DataKey::LoyaltyConfig) {
+            Some(config) => config,
+            None => return,
+        };
+
+        let mut account = Self::loyalty_account(env, user);
+        account.points += points;
+        account.expires_at = env.ledger().timestamp() + config.expiry_period;
+
+        env.storage()
+            .instance()
+            .set(&DataKey::LoyaltyAccounts(user.clone()), &account);
+
+        env.events().publish(
+            (Symbol::new(env, "loyalty_points_changed"), user.clone()),
+            account.points,
+        );
+    }
+
+    fn award_request_points(env: &Env, request: &TrackRequest) {
+        if request.amount_paid <= 0 {
+            return;
+        }
+        if let Some(config) = env
+            .storage()
+            .instance()
+            .get::<_, LoyaltyConfig>(&DataKey::LoyaltyConfig)
+        {
+            Self::credit_loyalty_points(env, &request.requester, config.points_per_request);
+        }
+    }
+
+    fn redeem_loyalty_points(env: &Env, user: &Address, points: i128, price: i128) -> i128 {
+        if points == 0 {
+            return 0;
+        }
+        if points < 0 {
+            panic!("Invalid points amount");
+        }
+
+        let config: LoyaltyConfig = env
+            .storage()
+            .instance()
+            .get(&DataKey::LoyaltyConfig)
+            .unwrap_or_else(|| panic!("Loyalty program not configured"));
+
+        let mut account = Self::loyalty_account(env, user);
+        if account.points < points {
+            panic!("Insufficient loyalty points");
+        }
+
+        let discount = points * config.point_value;
+        if discount > (price * config.max_discount_bps as i128) / 10000 {
+            panic!("Discount exceeds limit");
+        }
+
+        account.points -= points;
+        env.storage()
+            .instance()
+            .set(&DataKey::LoyaltyAccounts(user.clone()), &account);
+
+        env.events().publish(
+            (Symbol::new(env, "loyalty_points_changed"), user.clone()),
+            account.points,
+        );
+
+        discount
+    }
+
+    fn pay_referral_reward(env: &Env, referee: &Address, fee_amount: i128) -> i128 {
+        let config: ReferralConfig = match env.storage().instance().get(&DataKey::ReferralConfig) {
+            Some(config) => config,
+            None => return 0,
+        };
+        let mut referral: Referral = match env
+            .storage()
+            .instance()
+            .get(&DataKey::Referrals(referee.clone()))
+        {
+            Some(referral) => referral,
+            None => return 0,
+        };
+
+        if env.ledger().timestamp() > referral.referred_at + config.period {
+            return 0;
+        }
+
+        let reward =
+            ((fee_amount * config.reward_bps as i128) / 10000).min(config.cap - referral.earned);
+        if reward <= 0 {
+            return 0;
+        }
+
+        referral.earned += reward;
+        let mut stats = Self::get_referrer_stats(env.clone(), referral.referrer.clone());
+        stats.earned += reward;
+
+        let token_address: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::TokenStellar)
+            .unwrap();
+        let token_client = token::Client::new(env, &token_address);
+        token_client.transfer(&env.current_contract_address(), &referral.referrer, &reward);
+
+        env.storage()
+            .instance()
+            .set(&DataKey::ReferrerStats(referral.referrer.clone()), &stats);
+        env.storage()
+            .instance()
+            .set(&DataKey::Referrals(referee.clone()), &referral);
+
+        env.events().publish(
+            (Symbol::new(env, "referral_rewarded"), referral.referrer),
+            (referee.clone(), reward),
+        );
+
+        reward
+    }
+
+    fn split_royalties(
+        env: &Env,
+        track: &Track,
+        royalty_amount: &i128,
+        request_table: Option<BytesN<32>>,
+    ) {
+        let token_address: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::TokenStellar)
+            .unwrap();
+        let token_client = token::Client::new(env, &token_address);
+
+        for (artist_address, percentage) in track.royalty_split.iter() {
+            let artist_share = (royalty_amount * (percentage as i128)) / 100;
+
+            if env
+                .storage()
+                .instance()
+                .has(&DataKey::Artists(artist_address.clone()))
+            {
+                Self::credit_track_balance(env, &track.track_id, &artist_address, artist_share);
+                Self::record_earnings(
+                    env,
+                    &artist_address,
+                    &track.track_id,
+                    &request_table,
+                    &token_address,
+                    artist_share,
+                );
+            } else {
+                token_client.transfer(
+                    &env.current_contract_address(),
+                    &artist_address,
+                    &artist_share,
+                );
+            }
+        }
+    }
+
+    fn credit_track_balance(
+        env: &Env,
+        track_id: &BytesN<32>,
+        artist_address: &Address,
+        amount: i128,
+    ) {
+        let mut artist: Artist = env
+            .storage()
+            .instance()
+            .get(&DataKey::Artists(artist_address.clone()))
+            .unwrap();
+        artist.revenue_balance += amount;
+        env.storage()
+            .instance()
+            .set(&DataKey::Artists(artist_address.clone()), &artist);
+
+        let balance_key = DataKey::TrackBalances(track_id.clone(), artist_address.clone());
+        let balance: i128 = env.storage().instance().get(&balance_key).unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&balance_key, &(balance + amount));
+
+        let mut earning_tracks: Vec<BytesN<32>> = env
+            .storage()
+            .instance()
+            .get(&DataKey::ArtistEarningTracks(artist_address.clone()))
+            .unwrap_or_else(|| Vec::new(env));
+        if !earning_tracks.contains(track_id) {
+            earning_tracks.push_back(track_id.clone());
+            env.storage().instance().set(
+                &DataKey::ArtistEarningTracks(artist_address.clone()),
+                &earning_tracks,
+            );
+        }
+    }
+
+    fn record_earnings(
+        env: &Env,
+        artist: &Address,
+        track_id: &BytesN<32>,
+        request_table: &Option<BytesN<32>>,
+        token: &Address,
+        amount: i128,
+    ) {
+        let is_request = request_table.is_some();
+        Self::bump_earnings(
+            env,
+            DataKey::ArtistTrackEarnings(artist.clone(), track_id.clone()),
+            amount,
+            is_request,
+        );
+        Self::bump_earnings(
+            env,
+            DataKey::ArtistTokenEarnings(artist.clone(), token.clone()),
+            amount,
+            is_request,
+        );
+        if let Some(table_id) = request_table {
+            Self::bump_earnings(
+                env,
+                DataKey::ArtistTableEarnings(artist.clone(), table_id.clone()),
+                amount,
+                is_request,
+            );
+        }
+    }
+
+    fn bump_earnings(env: &Env, key: DataKey, amount: i128, is_request: bool) {
+        let mut stat: EarningsStat = env.storage().instance().get(&key).unwrap_or(EarningsStat {
+            earned: 0,
+            requests: 0,
+        });
+        stat.earned += amount;
+        if is_request {
+            stat.requests += 1;
+        }
+        env.storage().instance().set(&key, &stat);
+    }
+
+    /// Returns the bucket `timestamp` falls in and how many trailing buckets
+    /// the window spans.
+    fn leaderboard_bucket(window: LeaderboardWindow, timestamp: u64) -> (u64, u64) {
+        match window {
+            LeaderboardWindow::AllTime => (0, 1),
+            LeaderboardWindow::LastDay => (timestamp / 3600, 24),
+            LeaderboardWindow::LastWeek => (timestamp / 86400, 7),
+        }
+    }
+
+    fn rolling_score(
+        env: &Env,
+        kind: LeaderboardKind,
+        window: LeaderboardWindow,
+        subject: &LeaderboardSubject,
+    ) -> i128 {
+        let (current, span) = Self::leaderboard_bucket(window, env.ledger().timestamp());
+        let buckets: Vec<(u64, i128)> = env
+            .storage()
+            .instance()
+            .get(&DataKey::LeaderboardScores(kind, window, subject.clone()))
+            .unwrap_or_else(|| Vec::new(env));
+
+        let mut score = 0;
+        for (bucket, amount) in buckets.iter() {
+            if bucket + span > current {
+                score += amount;
+            }
+        }
+        score
+    }
+
+    /// Adds `amount` to `subject` on every window of the `kind` board, booked
+    /// against the bucket containing `at`. When the caller already tracks a
+    /// lifetime total it is passed as `all_time` and used directly instead of
+    /// keeping a second all-time counter.
+    fn bump_leaderboards(
+        env: &Env,
+        kind: LeaderboardKind,
+        subject: LeaderboardSubject,
+        amount: i128,
+        at: u64,
+        all_time: Option<i128>,
+    ) {
+        for window in [
+            LeaderboardWindow::AllTime,
+            LeaderboardWindow::LastDay,
+            LeaderboardWindow::LastWeek,
+        ] {
+            if let (LeaderboardWindow::AllTime, Some(total)) = (window, all_time) {
+                Self::place_on_leaderboard(env, kind, window, &subject, total);
+                continue;
+            }
+
+            let (current, span) = Self::leaderboard_bucket(window, env.ledger().timestamp());
+            let (target, _) = Self::leaderboard_bucket(window, at);
+            if target + span <= current {
+                continue;
+            }
+
+            let key = DataKey::LeaderboardScores(kind, window, subject.clone());
+            let buckets: Vec<(u64, i128)> = env
+                .storage()
+                .instance()
+                .get(&key)
+                .unwrap_or_else(|| Vec::new(env));
+
+            let mut kept = Vec::new(env);
+            let mut booked = false;
+            for (bucket, score) in buckets.iter() {
+                if bucket + span <= current {
+                    continue;
+                }
+                if bucket == target {
+                    kept.push_back((bucket, score + amount));
+                    booked = true;
+                } else {
+                    kept.push_back((bucket, score));
+                }
+            }
+            if !booked {
+                kept.push_back((target, amount));
+            }
+            env.storage().instance().set(&key, &kept);
+
+            let score = Self::rolling_score(env, kind, window, &subject);
+            Self::place_on_leaderboard(env, kind, window, &subject, score);
+        }
+    }
+
+    /// Loads the stored candidates, re-scoring rolling boards so entries whose
+    /// activity has aged out drop back or off.
+    fn leaderboard_candidates(
+        env: &Env,
+        kind: LeaderboardKind,
+        window: LeaderboardWindow,
+    ) -> Vec<LeaderboardEntry> {
+        let stored: Vec<LeaderboardEntry> = env
+            .storage()
+            .instance()
+            .get(&DataKey::Leaderboards(kind, window))
+            .unwrap_or_else(|| Vec::new(env));
+        if window == LeaderboardWindow::AllTime {
+            return stored;
+        }
+
+        let mut entries = Vec::new(env);
+        for entry in stored.iter() {
+            let score = Self::rolling_score(env, kind, window, &entry.subject);
+            Self::insert_ranked(
+                &mut entries,
+                LeaderboardEntry {
+                    subject: entry.subject,
+                    score,
+                },
+            );
+        }
+        entries
+    }
+
+    fn insert_ranked(entries: &mut Vec<LeaderboardEntry>, entry: LeaderboardEntry) {
+        if entry.score <= 0 {
+            return;
+        }
+
+        let position = entries
+            .iter()
+            .position(|ranked| ranked.score < entry.score)
+            .map(|index| index as u32)
+            .unwrap_or(entries.len());
+        if position >= LEADERBOARD_CANDIDATES {
+            return;
+        }
+
+        entries.insert(position, entry);
+        if entries.len() > LEADERBOARD_CANDIDATES {
+            entries.pop_back();
+        }
+    }
+
+    fn place_on_leaderboard(
+        env: &Env,
+        kind: LeaderboardKind,
+        window: LeaderboardWindow,
+        subject: &LeaderboardSubject,
+        score: i128,
+    ) {
+        let mut entries = Self::leaderboard_candidates(env, kind, window);
+
+        if let Some(index) = entries.iter().position(|entry| entry.subject == *subject) {
+            entries.remove(index as u32);
+        }
+        Self::insert_ranked(
+            &mut entries,
+            LeaderboardEntry {
+                subject: subject.clone(),
+                score,
+            },
+        );
+
+        env.storage()
+            .instance()
+            .set(&DataKey::Leaderboards(kind, window), &entries);
+    }
+
+    fn record_settlement(env: &Env, track_id: &BytesN<32>, requester: &Address, amount: &i128) {
+        let settled_count: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::SettledCount(track_id.clone()))
+            .unwrap_or(0);
+        let settled_total: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::SettledTotal(track_id.clone()))
+            .unwrap_or(0);
+
+        Self::list_set(
+            env,
+            &DataKey::SettledRequests(track_id.clone(), settled_count),
+            &(requester.clone(), *amount),
+        );
+        env.storage().instance().set(
+            &DataKey::SettledCount(track_id.clone()),
+            &(settled_count + 1),
+        );
+        env.storage().instance().set(
+            &DataKey::SettledTotal(track_id.clone()),
+            &(settled_total + amount),
+        );
+    }
+
+    pub fn get_artist_statement(
+        env: Env,
+        artist: Address,
+        track_id: Option<BytesN<32>>,
+        table_id: Option<BytesN<32>>,
+    ) -> ArtistStatement {
+        let artist_data: Artist = env
+            .storage()
+            .instance()
+            .get(&DataKey::Artists(artist.clone()))
+            .unwrap_or_else(|| panic!("Artist not found"));
+        let token: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::TokenStellar)
+            .unwrap();
+
+        let lifetime = env
+            .storage()
+            .instance()
+            .get(&DataKey::ArtistTokenEarnings(artist.clone(), token.clone()))
+            .unwrap_or(EarningsStat {
+                earned: 0,
+                requests: 0,
+            });
+        let track = track_id.map(|track_id| {
+            env.storage()
+                .instance()
+                .get(&DataKey::ArtistTrackEarnings(artist.clone(), track_id))
+                .unwrap_or(EarningsStat {
+                    earned: 0,
+                    requests: 0,
+                })
+        });
+        let table = table_id.map(|table_id| {
+            env.storage()
+                .instance()
+                .get(&DataKey::ArtistTableEarnings(artist.clone(), table_id))
+                .unwrap_or(EarningsStat {
+                    earned: 0,
+                    requests: 0,
+                })
+        });
+
+        ArtistStatement {
+            artist,
+            token,
+            revenue_balance: artist_data.revenue_balance,
+            lifetime,
+            track,
+            table,
+        }
+    }
+
+    pub fn withdraw_revenue(env: Env, artist: Address) -> i128 {
+        artist.require_auth();
+
+        if !env
+            .storage()
+            .instance()
+            .has(&DataKey::Artists(artist.clone()))
+        {
+            panic!("Not registered as artist");
+        }
+
+        let mut artist_data: Artist = env
+            .storage()
+            .instance()
+            .get(&DataKey::Artists(artist.clone()))
+            .unwrap();
+
+        let amount = artist_data.revenue_balance;
+        artist_data.revenue_balance = 0;
+        env.storage()
+            .instance()
+            .set(&DataKey::Artists(artist.clone()), &artist_data);
+
+        let earning_tracks: Vec<BytesN<32>> = env
+            .storage()
+            .instance()
+            .get(&DataKey::ArtistEarningTracks(artist.clone()))
+            .unwrap_or_else(|| Vec::new(&env));
+        for track_id in earning_tracks.iter() {
+            env.storage()
+                .instance()
+                .remove(&DataKey::TrackBalances(track_id, artist.clone()));
+        }
+        env.storage()
+            .instance()
+            .remove(&DataKey::ArtistEarningTracks(artist.clone()));
+
+        let token_address: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::TokenStellar)
+            .unwrap();
+        let token_client = token::Client::new(&env, &token_address);
+
+        token_client.transfer(&env.current_contract_address(), &artist, &amount);
+
+        amount
+    }
+
+    pub fn takedown_track(
+        env: Env,
+        moderator: Address,
+        track_id: BytesN<32>,
+        reason_uri: String,
+        table_ids: Vec<BytesN<32>>,
+    ) {
+        Self::require_moderator(&env, &moderator);
+
+        let track: Track = env
+            .storage()
+            .instance()
+            .get(&DataKey::Tracks(track_id.clone()))
+            .unwrap_or_else(|| panic!("Track not found"));
+
+        if Self::is_taken_down(&env, &track_id) {
+            panic!("Track already taken down");
+        }
+
+        let mut escrowed: i128 = 0;
+        let mut escrow_shares: Map<Address, i128> = Map::new(&env);
+        for (recipient, _) in track.royalty_split.iter() {
+            let balance_key = DataKey::TrackBalances(track_id.clone(), recipient.clone());
+            let balance: i128 = env.storage().instance().get(&balance_key).unwrap_or(0);
+            if balance == 0 {
+                continue;
+            }
+
+            let mut artist: Artist = env
+                .storage()
+                .instance()
+                .get(&DataKey::Artists(recipient.clone()))
+                .unwrap();
+            artist.revenue_balance -= balance;
+            env.storage()
+                .instance()
+                .set(&DataKey::Artists(recipient.clone()), &artist);
+            env.storage().instance().remove(&balance_key);
+
+            escrowed += balance;
+            escrow_shares.set(
+                recipient.clone(),
+                escrow_shares.get(recipient).unwrap_or(0) + balance,
+            );
+        }
+
+        let takedown = Takedown {
+            track_id: track_id.clone(),
+            moderator,
+            reason_uri,
+            counter_claim_uri: None,
+            taken_down_at: env.ledger().timestamp(),
+            status: TakedownStatus::Active,
+            escrowed,
+            escrow_shares,
+            refund_base: 0,
+            refund_cursor: 0,
+        };
+        env.storage()
+            .instance()
+            .set(&DataKey::Takedowns(track_id.clone()), &takedown);
+
+        for table_id in table_ids.iter() {
+            Self::purge_track_from_queue(&env, &table_id, &track_id);
+        }
+
+        env.events()
+            .publish((Symbol::new(&env, "track_taken_down"), track_id), escrowed);
+    }
+
+    pub fn purge_taken_down_track(env: Env, table_id: BytesN<32>, track_id: BytesN<32>) {
+        if !Self::is_taken_down(&env, &track_id) {
+            panic!("Track not taken down");
+        }
+
+        Self::purge_track_from_queue(&env, &table_id, &track_id);
+    }
+
+    pub fn file_counter_claim(
+        env: Env,
+        artist: Address,
+        track_id: BytesN<32>,
+        counter_claim_uri: String,
+    ) {
+        artist.require_auth();
+
+        let track: Track = env
+            .storage()
+            .instance()
+            .get(&DataKey::Tracks(track_id.clone()))
+            .unwrap_or_else(|| panic!("Track not found"));
+
+        if track.artist_id != artist {
+            panic!("Not track owner");
+        }
+
+        let mut takedown: Takedown = env
+            .storage()
+            .instance()
+            .get(&DataKey::Takedowns(track_id.clone()))
+            .unwrap_or_else(|| panic!("Track not taken down"));
+
+        if takedown.status != TakedownStatus::Active {
+            panic!("Takedown not open to counter-claims");
+        }
+
+        takedown.counter_claim_uri = Some(counter_claim_uri);
+        takedown.status = TakedownStatus::Contested;
+        env.storage()
+            .instance()
+            .set(&DataKey::Takedowns(track_id.clone()), &takedown);
+
+        env.events()
+            .publish((Symbol::new(&env, "counter_claim_filed"), track_id), ());
+    }
+
+    pub fn resolve_takedown(env: Env, track_id: BytesN<32>, release_to_artist: bool) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        let mut takedown: Takedown = env
+            .storage()
+            .instance()
+            .get(&DataKey::Takedowns(track_id.clone()))
+            .unwrap_or_else(|| panic!("Track not taken down"));
+
+        if takedown.status != TakedownStatus::Active && takedown.status != TakedownStatus::Contested
+        {
+            panic!("Takedown already resolved");
+        }
+
+        if release_to_artist {
+            for (recipient, amount) in takedown.escrow_shares.iter() {
+                Self::credit_track_balance(&env, &track_id, &recipient, amount);
+            }
+            takedown.escrowed = 0;
+            takedown.escrow_shares = Map::new(&env);
+            takedown.status = TakedownStatus::Released;
+        } else {
+            takedown.refund_base = env
+                .storage()
+                .instance()
+                .get(&DataKey::SettledTotal(track_id.clone()))
+                .unwrap_or(0);
+
+            let residual = takedown.escrowed - takedown.escrowed.min(takedown.refund_base);
+            if residual > 0 {
+                let token_address: Address = env
+                    .storage()
+                    .instance()
+                    .get(&DataKey::TokenStellar)
+                    .unwrap();
+                let token_client = token::Client::new(&env, &token_address);
+                token_client.transfer(&env.current_contract_address(), &admin, &residual);
+                takedown.escrowed -= residual;
+            }
+            takedown.escrow_shares = Map::new(&env);
+            takedown.status = TakedownStatus::Upheld;
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::Takedowns(track_id.clone()), &takedown);
+
+        env.events().publish(
+            (Symbol::new(&env, "takedown_resolved"), track_id),
+            release_to_artist,
+        );
+    }
+
+    pub fn process_takedown_refunds(env: Env, track_id: BytesN<32>, limit: u32) -> bool {
+        let mut takedown: Takedown = env
+            .storage()
+            .instance()
+            .get(&DataKey::Takedowns(track_id.clone()))
+            .unwrap_or_else(|| panic!("Track not taken down"));
+
+        if takedown.status != TakedownStatus::Upheld {
+            panic!("Takedown not upheld");
+        }
+
+        let settled_count: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::SettledCount(track_id.clone()))
+            .unwrap_or(0);
+
+        let token_address: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::TokenStellar)
+            .unwrap();
+        let token_client = token::Client::new(&env, &token_address);
+
+        let end = settled_count.min(takedown.refund_cursor.saturating_add(limit));
+        while takedown.refund_cursor < end && takedown.refund_base > 0 {
+            let settlement_key = DataKey::SettledRequests(track_id.clone(), takedown.refund_cursor);
+            let (requester, amount_paid): (Address, i128) =
+                env.storage().persistent().get(&settlement_key).unwrap();
+            env.storage().persistent().remove(&settlement_key);
+            takedown.refund_cursor += 1;
+
+            let refund = (takedown.escrowed * amount_paid) / takedown.refund_base;
+            if refund > 0 {
+                token_client.transfer(&env.current_contract_address(), &requester, &refund);
+            }
+        }
+
+        let complete = takedown.refund_cursor >= settled_count || takedown.refund_base == 0;
+        env.storage()
+            .instance()
+            .set(&DataKey::Takedowns(track_id), &takedown);
+
+        complete
+    }
+
+    pub fn get_takedown(env: Env, track_id: BytesN<32>) -> Option<Takedown> {
+        env.storage().instance().get(&DataKey::Takedowns(track_id))
+    }
+
+    fn is_taken_down(env: &Env, track_id: &BytesN<32>) -> bool {
+        match env
+            .storage()
+            .instance()
+            .get::<_, Takedown>(&DataKey::Takedowns(track_id.clone()))
+        {
+            Some(takedown) => takedown.status != TakedownStatus::Released,
+            None => false,
+        }
+    }
+
+    fn purge_track_from_queue(env: &Env, table_id: &BytesN<32>, track_id: &BytesN<32>) {
+        let mut table: JukeboxTable = match env
+            .storage()
+            .instance()
+            .get(&DataKey::Tables(table_id.clone()))
+        {
+            Some(table) => table,
+            None => return,
+        };
+
+        let mut index = 0;
+        while index < table.queue.len() {
+            if table.queue.get(index).unwrap() == *track_id {
+                table.queue.remove(index);
+                if let Some(request_id) = table.pending_requests.get(index) {
+                    table.pending_requests.remove(index);
+                    Self::remove_request(env, &mut table, &request_id);
+                }
+            } else {
+                index += 1;
+            }
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::Tables(table_id.clone()), &table);
+    }
+
+    fn verify_nft_ownership(env: &Env, user: &Address, nft_address: &Address) -> bool {
+       
+    }
+
+    
+    pub fn get_user(env: Env, user: Address) -> Option<User> {
+        env.storage().instance().get(&DataKey::Users(user))
+    }
+
+    pub fn get_suspension(env: Env, user: Address) -> Option<Suspension> {
+        env.storage().instance().get(&DataKey::Suspensions(user))
+    }
+
+    pub fn is_moderator(env: Env, user: Address) -> bool {
+        env.storage().instance().has(&DataKey::Moderators(user))
+    }
+
+    pub fn get_reputation(env: Env, user: Address) -> u32 {
+        if let Some(user_data) = Self::get_user(env.clone(), user) {
+            Self::current_reputation(&env, &user_data)
+        } else {
+            0
+        }
+    }
+
+    pub fn get_artist(env: Env, artist: Address) -> Option<Artist> {
+        env.storage().instance().get(&DataKey::Artists(artist))
+    }
+
+    pub fn get_track(env: Env, track_id: BytesN<32>) -> Option<Track> {
+        env.storage().instance().get(&DataKey::Tracks(track_id))
+    }
+
+    pub fn get_metadata_version(
+        env: Env,
+        track_id: BytesN<32>,
+        version: u32,
+    ) -> Option<MetadataVersion> {
+        env.storage()
+            .instance()
+            .get(&DataKey::TrackMetadataVersions(track_id, version))
+    }
+
+    pub fn get_metadata_history(
+        env: Env,
+        track_id: BytesN<32>,
+        cursor: u32,
+        limit: u32,
+    ) -> (Vec<MetadataVersion>, Option<u32>) {
+        if limit == 0 || limit > MAX_PAGE_SIZE {
+            panic!("Invalid page size");
+        }
+
+        let mut history = Vec::new(&env);
+        let track: Track = env
+            .storage()
+            .instance()
+            .get(&DataKey::Tracks(track_id.clone()))
+            .unwrap_or_else(|| panic!("Track not found"));
+
+        if cursor >= track.metadata_version {
+            return (history, None);
+        }
+
+        let end = track.metadata_version.min(cursor + limit);
+        for version in (cursor + 1)..=end {
+            if let Some(metadata) = env
+                .storage()
+                .instance()
+                .get(&DataKey::TrackMetadataVersions(track_id.clone(), version))
+            {
+                history.push_back(metadata);
+            }
+        }
+
+        let next_cursor = if end < track.metadata_version {
+            Some(end)
+        } else {
+            None
+        };
+        (history, next_cursor)
+    }
+
+    pub fn get_tag(env: Env, tag: Symbol) -> Option<Tag> {
+        env.storage().instance().get(&DataKey::Tags(tag))
+    }
+
+    pub fn get_tracks_by_tag(
+        env: Env,
+        tag: Symbol,
+        cursor: u32,
+        limit: u32,
+    ) -> (Vec<BytesN<32>>, Option<u32>) {
+        Self::list_page(&env, &IndexedList::TagTracks(tag), cursor, limit)
+    }
+
+    pub fn get_table(env: Env, table_id: BytesN<32>) -> Option<JukeboxTable> {
+        env.storage().instance().get(&DataKey::Tables(table_id))
+    }
+
+    pub fn get_queue(
+        env: Env,
+        table_id: BytesN<32>,
+        cursor: u32,
+        limit: u32,
+    ) -> (Vec<BytesN<32>>, Option<u32>) {
+        if let Some(table) = Self::get_table(env.clone(), table_id) {
+            Self::paginate(&env, &table.queue, cursor, limit)
+        } else {
+            (Vec::new(&env), None)
+        }
+    }
+
+    pub fn is_table_member(env: Env, user: Address, table_id: BytesN<32>) -> bool {
+        env.storage()
+            .instance()
+            .has(&DataKey::TableMembers(table_id, user))
+    }
+
+    pub fn is_table_admin(env: Env, user: Address, table_id: BytesN<32>) -> bool {
+        env.storage()
+            .instance()
+            .has(&DataKey::TableAdmins(table_id, user))
+    }
+
+    pub fn get_table_member_count(env: Env, table_id: BytesN<32>) -> u32 {
+        if let Some(table) = Self::get_table(env, table_id) {
+            table.member_count
+        } else {
+            0
+        }
+    }
+
+    pub fn join_table(env: Env, user: Address, table_id: BytesN<32>, invite_code: Option<Bytes>) {
+        user.require_auth();
+        Self::require_active_user(&env, &user);
+
+        let mut table: JukeboxTable = env
+            .storage()
+            .instance()
+            .get(&DataKey::Tables(table_id.clone()))
+            .unwrap_or_else(|| panic!("Table not found"));
+
+        if !table.is_active {
+            panic!("Table is closed");
+        }
+
+        if env
+            .storage()
+            .instance()
+            .has(&DataKey::TableMembers(table_id.clone(), user.clone()))
+        {
+            panic!("Already a member of this table");
+        }
+
+        if let Some(banned_until) = env
+            .storage()
+            .instance()
+            .get::<_, u64>(&DataKey::TableBans(table_id.clone(), user.clone()))
+        {
+            if banned_until > env.ledger().timestamp() {
+                panic!("Banned from this table");
+            }
+            env.storage()
+                .instance()
+                .remove(&DataKey::TableBans(table_id.clone(), user.clone()));
+            Self::list_remove(&env, &IndexedList::TableBans(table_id.clone()), &user);
+        }
+
+        if table.max_members > 0 && table.member_count >= table.max_members {
+            panic_with_error!(&env, LimitError::MemberLimitReached);
+        }
+
+        if table.owner != user {
+            match table.visibility {
+                TableVisibility::Public => {}
+                TableVisibility::Allowlist => {
+                    if !env
+                        .storage()
+                        .instance()
+                        .has(&DataKey::TableAllowlist(table_id.clone(), user.clone()))
+                    {
+                        panic!("Not on table allowlist");
+                    }
+                }
+                TableVisibility::InviteCode => {
+                    let code = invite_code.unwrap_or_else(|| panic!("Invite code required"));
+                    Self::redeem_invite_code(&env, &table_id, &code);
+                }
+            }
+        }
+
+        let mut expires_at = None;
+        if let Some(plan) = env
+            .storage()
+            .instance()
+            .get::<_, MembershipPlan>(&DataKey::TableMembershipPlans(table_id.clone()))
+        {
+            let token_address: Address = env
+                .storage()
+                .instance()
+                .get(&DataKey::TokenStellar)
+                .unwrap();
+            let token_client = token::Client::new(&env, &token_address);
+
+            let amount = plan.entry_fee + plan.period_price;
+            if amount > 0 {
+                token_client.transfer(&user, &env.current_contract_address(), &amount);
+                Self::pay_table_owner(&env, &table, &amount);
+            }
+
+            if plan.period_length > 0 {
+                expires_at = Some(env.ledger().timestamp() + plan.period_length);
+            }
+        }
+
+        Self::list_push(&env, &IndexedList::TableMembers(table_id.clone()), &user);
+
+        let membership = TableMembership {
+            member: user.clone(),
+            joined_at: env.ledger().timestamp(),
+            is_admin: false,
+            expires_at,
+        };
+
+        env.storage().instance().set(
+            &DataKey::TableMembers(table_id.clone(), user.clone()),
+            &membership,
+        );
+
+        Self::set_user_table(&env, &user, &table_id, true);
+
+        table.member_count += 1;
+        env.storage()
+            .instance()
+            .set(&DataKey::Tables(table_id.clone()), &table);
+
+        env.events().publish(
+            (Symbol::new(&env, "membership_changed"), table_id.clone()),
+            (user, true, false),
+        );
+    }
+
+    pub fn set_membership_plan(
+        env: Env,
+        owner: Address,
+        table_id: BytesN<32>,
+        entry_fee: i128,
+        period_price: i128,
+        period_length: u64,
+    ) {
+        owner.require_auth();
+
+        let table: JukeboxTable = env
+            .storage()
+            .instance()
+            .get(&DataKey::Tables(table_id.clone()))
+            .unwrap();
+
+        if table.owner != owner {
+            panic!("Not table owner");
+        }
+
+        if entry_fee < 0 || period_price < 0 {
+            panic!("Invalid membership price");
+        }
+
+        if period_price > 0 && period_length == 0 {
+            panic!("Subscription period required");
+        }
+
+        if entry_fee == 0 && period_price == 0 {
+            env.storage()
+                .instance()
+                .remove(&DataKey::TableMembershipPlans(table_id));
+            return;
+        }
+
+        env.storage().instance().set(
+            &DataKey::TableMembershipPlans(table_id),
+            &MembershipPlan {
+                entry_fee,
+                period_price,
+                period_length,
+            },
+        );
+    }
+
+    /// Pulls renewal fees from the member's allowance. Anyone may trigger a
+    /// single-period renewal within `RENEWAL_WINDOW` before expiry; renewing
+    /// early, after a lapse or for several periods needs the member's own
+    /// authorization.
+    pub fn renew_membership(env: Env, user: Address, table_id: BytesN<32>, periods: u32) -> u64 {
+        let table: JukeboxTable = env
+            .storage()
+            .instance()
+            .get(&DataKey::Tables(table_id.clone()))
+            .unwrap_or_else(|| panic!("Table not found"));
+
+        if !table.is_active {
+            panic!("Table is closed");
+        }
+
+        let plan: MembershipPlan = env
+            .storage()
+            .instance()
+            .get(&DataKey::TableMembershipPlans(table_id.clone()))
+            .unwrap_or_else(|| panic!("Table has no subscription"));
+
+        if plan.period_length == 0 {
+            panic!("Table has no subscription");
+        }
+
+        if periods == 0 || periods > MAX_RENEWAL_PERIODS {
+            panic!("Invalid renewal period");
+        }
+
+        let mut membership: TableMembership = env
+            .storage()
             .instance()
-            .set(&DataKey::TableIdCounter, &table_counter);
+            .get(&DataKey::TableMembers(table_id.clone(), user.clone()))
+            .unwrap_or_else(|| panic!("Not a member of this table"));
 
-        env.events()
-            .publish((Symbol::new(&env, "table_created"), table_id.clone()), ());
+        let current_expiry = match membership.expires_at {
+            Some(expiry) => expiry,
+            None => panic!("Membership does not expire"),
+        };
 
-        table_id
+        let now = env.ledger().timestamp();
+        let in_renewal_window = now <= current_expiry && now + RENEWAL_WINDOW >= current_expiry;
+        if periods > 1 || !in_renewal_window {
+            user.require_auth();
+        }
+
+        let amount = plan.period_price * periods as i128;
+        if amount > 0 {
+            let token_address: Address = env
+                .storage()
+                .instance()
+                .get(&DataKey::TokenStellar)
+                .unwrap();
+            let token_client = token::Client::new(&env, &token_address);
+            token_client.transfer_from(
+                &env.current_contract_address(),
+                &user,
+                &env.current_contract_address(),
+                &amount,
+            );
+            Self::pay_table_owner(&env, &table, &amount);
+        }
+
+        let new_expiry = current_expiry.max(now) + plan.period_length * periods as u64;
+        membership.expires_at = Some(new_expiry);
+
+        env.storage().instance().set(
+            &DataKey::TableMembers(table_id.clone(), user.clone()),
+            &membership,
+        );
+
+        env.events().publish(
+            (Symbol::new(&env, "membership_renewed"), table_id),
+            (user, new_expiry),
+        );
+
+        new_expiry
     }
 
-    pub fn update_table(
+    pub fn set_table_visibility(
         env: Env,
         owner: Address,
         table_id: BytesN<32>,
-        name: String,
-        skip_threshold: u32,
-        price_multiplier: u32,
+        visibility: TableVisibility,
     ) {
         owner.require_auth();
 
@@ -417,278 +4833,436 @@ impl MetaJuke {
             panic!("Not table owner");
         }
 
-        table.name = name;
-        table.skip_threshold = skip_threshold;
-        table.price_multiplier = price_multiplier;
-
+        table.visibility = visibility;
         env.storage()
             .instance()
             .set(&DataKey::Tables(table_id), &table);
     }
 
-    pub fn request_track(
+    pub fn update_allowlist(
         env: Env,
-        requester: Address,
-        track_id: BytesN<32>,
+        owner: Address,
         table_id: BytesN<32>,
-    ) -> BytesN<32> {
-        
-    }
+        users: Vec<Address>,
+        allowed: bool,
+    ) {
+        owner.require_auth();
 
-    pub fn vote_to_skip(env: Env, user: Address, table_id: BytesN<32>) -> bool {
-        user.require_auth();
+        let table: JukeboxTable = env
+            .storage()
+            .instance()
+            .get(&DataKey::Tables(table_id.clone()))
+            .unwrap();
 
-        if !env.storage().instance().has(&DataKey::Users(user.clone())) {
-            panic!("User not registered");
+        if table.owner != owner {
+            panic!("Not table owner");
         }
 
-        let mut table: JukeboxTable = env
+        let allowlist = IndexedList::TableAllowlist(table_id.clone());
+        for user in users.iter() {
+            let key = DataKey::TableAllowlist(table_id.clone(), user.clone());
+            if allowed {
+                env.storage().instance().set(&key, &true);
+                Self::list_push(&env, &allowlist, &user);
+            } else {
+                env.storage().instance().remove(&key);
+                Self::list_remove(&env, &allowlist, &user);
+            }
+        }
+    }
+
+    pub fn add_invite_code(
+        env: Env,
+        owner: Address,
+        table_id: BytesN<32>,
+        code_hash: BytesN<32>,
+        max_uses: u32,
+    ) {
+        owner.require_auth();
+
+        let table: JukeboxTable = env
             .storage()
             .instance()
             .get(&DataKey::Tables(table_id.clone()))
             .unwrap();
 
-        if table.current_track.is_none() {
-            panic!("No track currently playing");
+        if table.owner != owner {
+            panic!("Not table owner");
         }
 
-        table.skip_votes.set(user.clone(), true);
-        let vote_count = table.skip_votes.values().into_iter().filter(|&v| v).count();
-        let should_skip = vote_count >= table.skip_threshold as usize;
-
-        if should_skip {
-            Self::advance_queue(&env, table_id.clone());
-            table.skip_votes = Map::new(&env);
-            env.storage()
-                .instance()
-                .set(&DataKey::Tables(table_id), &table);
-            true
-        } else {
-            env.storage()
-                .instance()
-                .set(&DataKey::Tables(table_id), &table);
-            false
+        if max_uses == 0 {
+            panic!("Invite code needs at least one use");
         }
+
+        env.storage().instance().set(
+            &DataKey::TableInvites(table_id.clone(), code_hash.clone()),
+            &InviteCode { max_uses, uses: 0 },
+        );
+        Self::list_push(&env, &IndexedList::TableInvites(table_id), &code_hash);
     }
 
-    pub fn advance_queue(env: &Env, table_id: BytesN<32>) -> Option<BytesN<32>> {
-        let mut table: JukeboxTable = env
+    pub fn revoke_invite_code(
+        env: Env,
+        owner: Address,
+        table_id: BytesN<32>,
+        code_hash: BytesN<32>,
+    ) {
+        owner.require_auth();
+
+        let table: JukeboxTable = env
             .storage()
             .instance()
             .get(&DataKey::Tables(table_id.clone()))
             .unwrap();
 
-        if table.queue.is_empty() {
-            table.current_track = None;
-            env.storage()
-                .instance()
-                .set(&DataKey::Tables(table_id.clone()), &table);
-            return None;
+        if table.owner != owner {
+            panic!("Not table owner");
         }
 
-        let next_track = table.queue.pop_front().unwrap();
-        table.current_track = Some(next_track.clone());
-        table.skip_votes = Map::new(env);
         env.storage()
             .instance()
-            .set(&DataKey::Tables(table_id.clone()), &table);
+            .remove(&DataKey::TableInvites(table_id.clone(), code_hash.clone()));
+        Self::list_remove(&env, &IndexedList::TableInvites(table_id), &code_hash);
+    }
 
-        Some(next_track)
+    pub fn is_on_allowlist(env: Env, table_id: BytesN<32>, user: Address) -> bool {
+        env.storage()
+            .instance()
+            .has(&DataKey::TableAllowlist(table_id, user))
     }
 
-    fn distribute_royalties(env: &Env, track: &Track, payment_amount: &i128) {
-        let platform_fee: u32 = env.storage().instance().get(&DataKey::PlatformFee).unwrap();
+    pub fn get_invite_code(
+        env: Env,
+        table_id: BytesN<32>,
+        code_hash: BytesN<32>,
+    ) -> Option<InviteCode> {
+        env.storage()
+            .instance()
+            .get(&DataKey::TableInvites(table_id, code_hash))
+    }
 
-        let fee_amount = (payment_amount * platform_fee as i128) / 10000;
-        let royalty_amount = payment_amount - fee_amount;
+    pub fn get_membership_plan(env: Env, table_id: BytesN<32>) -> Option<MembershipPlan> {
+        env.storage()
+            .instance()
+            .get(&DataKey::TableMembershipPlans(table_id))
+    }
 
-        let token_address: Address = env
-            .storage()
+    pub fn get_membership(
+        env: Env,
+        table_id: BytesN<32>,
+        user: Address,
+    ) -> Option<TableMembership> {
+        env.storage()
             .instance()
-            .get(&DataKey::TokenStellar)
-            .unwrap();
-        let token_client = token::Client::new(env, &token_address);
+            .get(&DataKey::TableMembers(table_id, user))
+    }
 
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
-        token_client.transfer(&env.current_contract_address(), &admin, &fee_amount);
+    pub fn leave_table(env: Env, user: Address, table_id: BytesN<32>) {
+        user.require_auth();
 
-        for (artist_address, percentage) in track.royalty_split.iter() {
-            let artist_share = (royalty_amount * (percentage as i128)) / 100;
+        Self::remove_member(&env, &table_id, &user);
+    }
 
-            if env
-                .storage()
-                .instance()
-                .has(&DataKey::Artists(artist_address.clone()))
-            {
-                let mut artist: Artist = env
-                    .storage()
-                    .instance()
-                    .get(&DataKey::Artists(artist_address.clone()))
-                    .unwrap();
-                artist.revenue_balance += artist_share;
-                env.storage()
-                    .instance()
-                    .set(&DataKey::Artists(artist_address.clone()), &artist);
-            }
+    pub fn kick_member(env: Env, caller: Address, table_id: BytesN<32>, member: Address) {
+        let table = Self::require_table_moderator(&env, &caller, &table_id);
+        Self::check_moderation_target(&env, &caller, &table, &table_id, &member);
 
-            token_client.transfer(
-                &env.current_contract_address(),
-                &artist_address,
-                &artist_share,
-            );
-        }
+        Self::remove_member(&env, &table_id, &member);
+
+        env.events()
+            .publish((Symbol::new(&env, "member_kicked"), table_id), member);
     }
 
-    pub fn withdraw_revenue(env: Env, artist: Address) -> i128 {
-        artist.require_auth();
+    pub fn ban_member(
+        env: Env,
+        caller: Address,
+        table_id: BytesN<32>,
+        member: Address,
+        banned_until: u64,
+    ) {
+        let table = Self::require_table_moderator(&env, &caller, &table_id);
+        Self::check_moderation_target(&env, &caller, &table, &table_id, &member);
 
-        if !env
+        if banned_until <= env.ledger().timestamp() {
+            panic!("Ban must end in the future");
+        }
+
+        if env
             .storage()
             .instance()
-            .has(&DataKey::Artists(artist.clone()))
+            .has(&DataKey::TableMembers(table_id.clone(), member.clone()))
         {
-            panic!("Not registered as artist");
+            Self::remove_member(&env, &table_id, &member);
         }
 
-        let mut artist_data: Artist = env
-            .storage()
-            .instance()
-            .get(&DataKey::Artists(artist.clone()))
-            .unwrap();
+        env.storage().instance().set(
+            &DataKey::TableBans(table_id.clone(), member.clone()),
+            &banned_until,
+        );
+        Self::list_push(&env, &IndexedList::TableBans(table_id.clone()), &member);
+
+        env.events().publish(
+            (Symbol::new(&env, "member_banned"), table_id),
+            (member, banned_until),
+        );
+    }
+
+    pub fn unban_member(env: Env, caller: Address, table_id: BytesN<32>, member: Address) {
+        Self::require_table_moderator(&env, &caller, &table_id);
 
-        let amount = artist_data.revenue_balance;
-        artist_data.revenue_balance = 0;
         env.storage()
             .instance()
-            .set(&DataKey::Artists(artist.clone()), &artist_data);
+            .remove(&DataKey::TableBans(table_id.clone(), member.clone()));
+        Self::list_remove(&env, &IndexedList::TableBans(table_id), &member);
+    }
 
-        let token_address: Address = env
-            .storage()
-            .instance()
-            .get(&DataKey::TokenStellar)
-            .unwrap();
-        let token_client = token::Client::new(&env, &token_address);
+    pub fn remove_queued_request(
+        env: Env,
+        caller: Address,
+        table_id: BytesN<32>,
+        request_id: BytesN<32>,
+    ) {
+        let mut table = Self::require_table_moderator(&env, &caller, &table_id);
 
-        token_client.transfer(&env.current_contract_address(), &artist, &amount);
+        let index = table
+            .pending_requests
+            .first_index_of(&request_id)
+            .unwrap_or_else(|| panic!("Request not queued"));
+        table.pending_requests.remove(index);
+        table.queue.remove(index);
 
-        amount
-    }
+        Self::remove_request(&env, &mut table, &request_id);
 
-    fn verify_nft_ownership(env: &Env, user: &Address, nft_address: &Address) -> bool {
-       
-    }
+        env.storage()
+            .instance()
+            .set(&DataKey::Tables(table_id.clone()), &table);
 
-    
-    pub fn get_user(env: Env, user: Address) -> Option<User> {
-        env.storage().instance().get(&DataKey::Users(user))
+        env.events()
+            .publish((Symbol::new(&env, "request_removed"), table_id), request_id);
     }
 
-    pub fn get_artist(env: Env, artist: Address) -> Option<Artist> {
-        env.storage().instance().get(&DataKey::Artists(artist))
-    }
+    pub fn set_track_blocked(
+        env: Env,
+        caller: Address,
+        table_id: BytesN<32>,
+        track_id: BytesN<32>,
+        blocked: bool,
+    ) {
+        Self::require_table_moderator(&env, &caller, &table_id);
 
-    pub fn get_track(env: Env, track_id: BytesN<32>) -> Option<Track> {
-        env.storage().instance().get(&DataKey::Tracks(track_id))
-    }
+        let key = DataKey::TableBlockedTracks(table_id.clone(), track_id.clone());
+        let blocklist = IndexedList::TableBlockedTracks(table_id.clone());
+        if blocked {
+            env.storage().instance().set(&key, &true);
+            Self::list_push(&env, &blocklist, &track_id);
+        } else {
+            env.storage().instance().remove(&key);
+            Self::list_remove(&env, &blocklist, &track_id);
+        }
 
-    pub fn get_table(env: Env, table_id: BytesN<32>) -> Option<JukeboxTable> {
-        env.storage().instance().get(&DataKey::Tables(table_id))
+        env.events()
+            .publish((Symbol::new(&env, "blocklist_changed"), table_id), blocked);
     }
 
-    pub fn get_queue(env: Env, table_id: BytesN<32>) -> Vec<BytesN<32>> {
-        if let Some(table) = Self::get_table(env.clone(), table_id) {
-            table.queue
+    pub fn set_artist_blocked(
+        env: Env,
+        caller: Address,
+        table_id: BytesN<32>,
+        artist: Address,
+        blocked: bool,
+    ) {
+        Self::require_table_moderator(&env, &caller, &table_id);
+
+        let key = DataKey::TableBlockedArtists(table_id.clone(), artist.clone());
+        let blocklist = IndexedList::TableBlockedArtists(table_id.clone());
+        if blocked {
+            env.storage().instance().set(&key, &true);
+            Self::list_push(&env, &blocklist, &artist);
         } else {
-            Vec::new(&env)
+            env.storage().instance().remove(&key);
+            Self::list_remove(&env, &blocklist, &artist);
         }
+
+        env.events()
+            .publish((Symbol::new(&env, "blocklist_changed"), table_id), blocked);
     }
 
-    pub fn is_table_member(env: Env, user: Address, table_id: BytesN<32>) -> bool {
-        env.storage()
+    pub fn is_track_blocked(env: Env, table_id: BytesN<32>, track_id: BytesN<32>) -> bool {
+        let track: Track = env
+            .storage()
             .instance()
-            .has(&DataKey::TableMembers(table_id, user))
+            .get(&DataKey::Tracks(track_id))
+            .unwrap_or_else(|| panic!("Track not found"));
+
+        Self::is_blocked_on_table(&env, &table_id, &track)
     }
 
-    pub fn is_table_admin(env: Env, user: Address, table_id: BytesN<32>) -> bool {
+    pub fn get_ban(env: Env, table_id: BytesN<32>, user: Address) -> Option<u64> {
         env.storage()
             .instance()
-            .has(&DataKey::TableAdmins(table_id, user))
+            .get(&DataKey::TableBans(table_id, user))
     }
 
-    pub fn get_table_member_count(env: Env, table_id: BytesN<32>) -> u32 {
-        if let Some(table) = Self::get_table(env, table_id) {
-            table.member_count
-        } else {
-            0
+    fn require_table_moderator(env: &Env, caller: &Address, table_id: &BytesN<32>) -> JukeboxTable {
+        caller.require_auth();
+
+        let table: JukeboxTable = env
+            .storage()
+            .instance()
+            .get(&DataKey::Tables(table_id.clone()))
+            .unwrap_or_else(|| panic!("Table not found"));
+
+        if table.owner != *caller
+            && !env
+                .storage()
+                .instance()
+                .has(&DataKey::TableAdmins(table_id.clone(), caller.clone()))
+        {
+            panic!("Not authorized");
         }
+
+        table
     }
 
-    pub fn join_table(env: Env, user: Address, table_id: BytesN<32>) {
-        user.require_auth();
+    fn check_moderation_target(
+        env: &Env,
+        caller: &Address,
+        table: &JukeboxTable,
+        table_id: &BytesN<32>,
+        target: &Address,
+    ) {
+        if table.owner == *target {
+            panic!("Cannot moderate table owner");
+        }
 
-        if !env.storage().instance().has(&DataKey::Users(user.clone())) {
-            panic!("User not registered");
+        if table.owner != *caller
+            && env
+                .storage()
+                .instance()
+                .has(&DataKey::TableAdmins(table_id.clone(), target.clone()))
+        {
+            panic!("Only owner can moderate admins");
         }
+    }
 
+    fn remove_member(env: &Env, table_id: &BytesN<32>, user: &Address) {
         let mut table: JukeboxTable = env
             .storage()
             .instance()
             .get(&DataKey::Tables(table_id.clone()))
             .unwrap_or_else(|| panic!("Table not found"));
 
-        if !table.is_active {
-            panic!("Table is closed");
-        }
-
-        if env
+        if !env
             .storage()
             .instance()
             .has(&DataKey::TableMembers(table_id.clone(), user.clone()))
         {
-            panic!("Already a member of this table");
+            panic!("Not a member of this table");
         }
-        let mut members: Vec<Address> = env
-            .storage()
+
+        env.storage()
             .instance()
-            .get(&DataKey::TableMemberList(table_id.clone()))
-            .unwrap_or_else(|| Vec::new(&env));
+            .remove(&DataKey::TableMembers(table_id.clone(), user.clone()));
+        env.storage()
+            .instance()
+            .remove(&DataKey::TableAdmins(table_id.clone(), user.clone()));
+        Self::set_user_table(env, user, table_id, false);
 
-        if !members.contains(&user) {
-            members.push_back(user.clone());
-            env.storage()
-                .instance()
-                .set(&DataKey::TableMemberList(table_id.clone()), &members);
+        if Self::list_remove(env, &IndexedList::TableMembers(table_id.clone()), user) {
+            table.member_count = table.member_count.saturating_sub(1);
         }
 
-        let membership = TableMembership {
-            member: user.clone(),
-            joined_at: env.ledger().timestamp(),
-            is_admin: false,
-        };
+        table.skip_votes.remove(user.clone());
+        env.storage()
+            .instance()
+            .set(&DataKey::Tables(table_id.clone()), &table);
 
-        env.storage().instance().set(
-            &DataKey::TableMembers(table_id.clone(), user.clone()),
-            &membership,
+        env.events().publish(
+            (Symbol::new(env, "membership_changed"), table_id.clone()),
+            (user.clone(), false, false),
         );
+    }
 
-        env.storage()
+    fn require_table_member(env: &Env, table_id: &BytesN<32>, user: &Address) {
+        let membership: TableMembership = env
+            .storage()
             .instance()
-            .set(&DataKey::UserTables(user.clone(), table_id.clone()), &true);
+            .get(&DataKey::TableMembers(table_id.clone(), user.clone()))
+            .unwrap_or_else(|| panic!("Not a member of this table"));
 
-        table.member_count += 1;
-        env.storage()
+        if let Some(expiry) = membership.expires_at {
+            if expiry <= env.ledger().timestamp() {
+                panic!("Membership expired");
+            }
+        }
+    }
+
+    fn redeem_invite_code(env: &Env, table_id: &BytesN<32>, code: &Bytes) {
+        let code_hash: BytesN<32> = env.crypto().sha256(code).into();
+        let key = DataKey::TableInvites(table_id.clone(), code_hash.clone());
+
+        let mut invite: InviteCode = env
+            .storage()
             .instance()
-            .set(&DataKey::Tables(table_id.clone()), &table);
+            .get(&key)
+            .unwrap_or_else(|| panic!("Invalid invite code"));
+
+        invite.uses += 1;
+        if invite.uses >= invite.max_uses {
+            env.storage().instance().remove(&key);
+            Self::list_remove(
+                env,
+                &IndexedList::TableInvites(table_id.clone()),
+                &code_hash,
+            );
+        } else {
+            env.storage().instance().set(&key, &invite);
+        }
+    }
 
-        env.events().publish(
-            (Symbol::new(&env, "membership_changed"), table_id.clone()),
-            (user, true, false),
+    fn pay_table_owner(env: &Env, table: &JukeboxTable, amount: &i128) {
+        let platform_fee: u32 = env.storage().instance().get(&DataKey::PlatformFee).unwrap();
+        let fee_amount = (amount * platform_fee as i128) / 10000;
+
+        let token_address: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::TokenStellar)
+            .unwrap();
+        let token_client = token::Client::new(env, &token_address);
+
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        token_client.transfer(&env.current_contract_address(), &admin, &fee_amount);
+        token_client.transfer(
+            &env.current_contract_address(),
+            &table.owner,
+            &(amount - fee_amount),
         );
     }
 
-    pub fn leave_table(env: Env, user: Address, table_id: BytesN<32>) {
-      
+    fn set_user_table(env: &Env, user: &Address, table_id: &BytesN<32>, joined: bool) {
+        let list = IndexedList::UserTables(user.clone());
+        if joined {
+            env.storage()
+                .instance()
+                .set(&DataKey::UserTables(user.clone(), table_id.clone()), &true);
+            Self::list_push(env, &list, table_id);
+        } else {
+            env.storage()
+                .instance()
+                .remove(&DataKey::UserTables(user.clone(), table_id.clone()));
+            Self::list_remove(env, &list, table_id);
+        }
+    }
 
+    fn is_blocked_on_table(env: &Env, table_id: &BytesN<32>, track: &Track) -> bool {
+        env.storage().instance().has(&DataKey::TableBlockedTracks(
+            table_id.clone(),
+            track.track_id.clone(),
+        )) || env.storage().instance().has(&DataKey::TableBlockedArtists(
+            table_id.clone(),
+            track.artist_id.clone(),
+        ))
     }
 
     pub fn add_table_admin(env: Env, owner: Address, table_id: BytesN<32>, new_admin: Address) {
@@ -708,15 +5282,29 @@ impl MetaJuke {
             member: new_admin.clone(),
             joined_at: env.ledger().timestamp(),
             is_admin: true,
+            expires_at: None,
         };
 
         env.storage().instance().set(
             &DataKey::TableMembers(table_id.clone(), new_admin.clone()),
             &admin_membership,
         );
-        env.storage()
+        env.storage().instance().set(
+            &DataKey::TableAdmins(table_id.clone(), new_admin.clone()),
+            &true,
+        );
+
+        let mut admins: Vec<Address> = env
+            .storage()
             .instance()
-            .set(&DataKey::TableAdmins(table_id, new_admin), &true);
+            .get(&DataKey::TableAdminList(table_id.clone()))
+            .unwrap_or_else(|| Vec::new(&env));
+        if !admins.contains(&new_admin) {
+            admins.push_back(new_admin);
+            env.storage()
+                .instance()
+                .set(&DataKey::TableAdminList(table_id), &admins);
+        }
     }
 
     pub fn remove_table_admin(env: Env, owner: Address, table_id: BytesN<32>, admin: Address) {
@@ -740,6 +5328,7 @@ impl MetaJuke {
             member: admin.clone(),
             joined_at: env.ledger().timestamp(),
             is_admin: false,
+            expires_at: None,
         };
 
         env.storage()
@@ -747,6 +5336,141 @@ impl MetaJuke {
             .set(&DataKey::TableMembers(table_id, admin), &membership);
     }
 
+    pub fn propose_table_owner(env: Env, owner: Address, table_id: BytesN<32>, new_owner: Address) {
+        owner.require_auth();
+
+        let table: JukeboxTable = env
+            .storage()
+            .instance()
+            .get(&DataKey::Tables(table_id.clone()))
+            .unwrap_or_else(|| panic!("Table not found"));
+
+        if table.owner != owner {
+            panic!("Not table owner");
+        }
+
+        if new_owner == owner {
+            panic!("Already table owner");
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::PendingTableOwners(table_id.clone()), &new_owner);
+
+        env.events().publish(
+            (Symbol::new(&env, "ownership_proposed"), table_id),
+            new_owner,
+        );
+    }
+
+    pub fn cancel_table_owner_proposal(env: Env, owner: Address, table_id: BytesN<32>) {
+        owner.require_auth();
+
+        let table: JukeboxTable = env
+            .storage()
+            .instance()
+            .get(&DataKey::Tables(table_id.clone()))
+            .unwrap_or_else(|| panic!("Table not found"));
+
+        if table.owner != owner {
+            panic!("Not table owner");
+        }
+
+        env.storage()
+            .instance()
+            .remove(&DataKey::PendingTableOwners(table_id));
+    }
+
+    pub fn accept_table_ownership(env: Env, new_owner: Address, table_id: BytesN<32>) {
+        new_owner.require_auth();
+        Self::require_active_user(&env, &new_owner);
+
+        let pending_owner: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::PendingTableOwners(table_id.clone()))
+            .unwrap_or_else(|| panic!("No pending ownership transfer"));
+
+        if pending_owner != new_owner {
+            panic!("Not pending table owner");
+        }
+
+        let mut table: JukeboxTable = env
+            .storage()
+            .instance()
+            .get(&DataKey::Tables(table_id.clone()))
+            .unwrap();
+        let previous_owner = table.owner.clone();
+
+        env.storage().instance().remove(&DataKey::TableAdmins(
+            table_id.clone(),
+            previous_owner.clone(),
+        ));
+        if let Some(mut membership) =
+            env.storage()
+                .instance()
+                .get::<_, TableMembership>(&DataKey::TableMembers(
+                    table_id.clone(),
+                    previous_owner.clone(),
+                ))
+        {
+            membership.is_admin = false;
+            env.storage().instance().set(
+                &DataKey::TableMembers(table_id.clone(), previous_owner.clone()),
+                &membership,
+            );
+        }
+
+        if Self::list_push(
+            &env,
+            &IndexedList::TableMembers(table_id.clone()),
+            &new_owner,
+        ) {
+            table.member_count += 1;
+        }
+
+        let joined_at = env
+            .storage()
+            .instance()
+            .get::<_, TableMembership>(&DataKey::TableMembers(table_id.clone(), new_owner.clone()))
+            .map(|membership| membership.joined_at)
+            .unwrap_or(env.ledger().timestamp());
+        let owner_membership = TableMembership {
+            member: new_owner.clone(),
+            joined_at,
+            is_admin: true,
+            expires_at: None,
+        };
+
+        env.storage().instance().set(
+            &DataKey::TableMembers(table_id.clone(), new_owner.clone()),
+            &owner_membership,
+        );
+        env.storage()
+            .instance()
+            .remove(&DataKey::TableAdmins(table_id.clone(), new_owner.clone()));
+        Self::set_user_table(&env, &new_owner, &table_id, true);
+        env.storage()
+            .instance()
+            .remove(&DataKey::PendingTableOwners(table_id.clone()));
+
+        table.owner = new_owner.clone();
+        env.storage()
+            .instance()
+            .set(&DataKey::Tables(table_id.clone()), &table);
+
+        env.events().publish(
+            (Symbol::new(&env, "ownership_transferred"), table_id),
+            (previous_owner, new_owner),
+        );
+    }
+
+    pub fn get_pending_table_owner(env: Env, table_id: BytesN<32>) -> Option<Address> {
+        env.storage()
+            .instance()
+            .get(&DataKey::PendingTableOwners(table_id))
+    }
+
     pub fn set_table_status(env: Env, owner: Address, table_id: BytesN<32>, active: bool) {
         owner.require_auth();
 
@@ -760,11 +5484,18 @@ impl MetaJuke {
             panic!("Not table owner");
         }
 
+        if env
+            .storage()
+            .instance()
+            .has(&DataKey::TableSummaries(table_id.clone()))
+        {
+            panic!("Table is archived");
+        }
+
         table.is_active = active;
 
         if !active {
-            table.queue = Vec::new(&env);
-            table.current_track = None;
+            Self::clear_table_queue(&env, &mut table);
         }
 
         env.storage()
@@ -777,6 +5508,222 @@ impl MetaJuke {
         );
     }
 
+    pub fn archive_table(env: Env, owner: Address, table_id: BytesN<32>) {
+        owner.require_auth();
+
+        let mut table: JukeboxTable = env
+            .storage()
+            .instance()
+            .get(&DataKey::Tables(table_id.clone()))
+            .unwrap_or_else(|| panic!("Table not found"));
+
+        if table.owner != owner {
+            panic!("Not table owner");
+        }
+
+        if env
+            .storage()
+            .instance()
+            .has(&DataKey::TableSummaries(table_id.clone()))
+        {
+            panic!("Table is archived");
+        }
+
+        table.is_active = false;
+        Self::clear_table_queue(&env, &mut table);
+
+        let summary = TableSummary {
+            table_id: table_id.clone(),
+            name: table.name.clone(),
+            owner,
+            archived_at: env.ledger().timestamp(),
+            member_count: table.member_count,
+            total_requests: table.total_requests,
+            total_plays: table.total_plays,
+            total_revenue: table.total_revenue,
+            cleanup_complete: false,
+        };
+
+        env.storage()
+            .instance()
+            .set(&DataKey::Tables(table_id.clone()), &table);
+        env.storage()
+            .instance()
+            .set(&DataKey::TableSummaries(table_id.clone()), &summary);
+        env.storage()
+            .instance()
+            .remove(&DataKey::PendingTableOwners(table_id.clone()));
+        env.storage()
+            .instance()
+            .remove(&DataKey::TableMembershipPlans(table_id.clone()));
+
+        env.events()
+            .publish((Symbol::new(&env, "table_archived"), table_id), ());
+    }
+
+    pub fn reclaim_table_storage(env: Env, table_id: BytesN<32>, limit: u32) -> bool {
+        let mut summary: TableSummary = env
+            .storage()
+            .instance()
+            .get(&DataKey::TableSummaries(table_id.clone()))
+            .unwrap_or_else(|| panic!("Table not archived"));
+
+        if summary.cleanup_complete {
+            return true;
+        }
+
+        let mut remaining = limit;
+
+        let members = IndexedList::TableMembers(table_id.clone());
+        while remaining > 0 {
+            let member: Address = match Self::list_pop(&env, &members) {
+                Some(member) => member,
+                None => break,
+            };
+
+            env.storage()
+                .instance()
+                .remove(&DataKey::TableMembers(table_id.clone(), member.clone()));
+            env.storage()
+                .instance()
+                .remove(&DataKey::TableAdmins(table_id.clone(), member.clone()));
+            Self::set_user_table(&env, &member, &table_id, false);
+            env.storage()
+                .instance()
+                .remove(&DataKey::PendingRequestCount(table_id.clone(), member));
+            remaining -= 1;
+        }
+
+        // Admins added by the owner hold membership entries without being on
+        // the member list.
+        let mut admins: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&DataKey::TableAdminList(table_id.clone()))
+            .unwrap_or_else(|| Vec::new(&env));
+        while remaining > 0 {
+            let admin = match admins.pop_back() {
+                Some(admin) => admin,
+                None => break,
+            };
+
+            env.storage()
+                .instance()
+                .remove(&DataKey::TableMembers(table_id.clone(), admin.clone()));
+            env.storage()
+                .instance()
+                .remove(&DataKey::TableAdmins(table_id.clone(), admin.clone()));
+            Self::set_user_table(&env, &admin, &table_id, false);
+            env.storage()
+                .instance()
+                .remove(&DataKey::PendingRequestCount(table_id.clone(), admin));
+            remaining -= 1;
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::TableAdminList(table_id.clone()), &admins);
+
+        let request_ids = IndexedList::TableRequests(table_id.clone());
+        while remaining > 0 {
+            let request_id: BytesN<32> = match Self::list_pop(&env, &request_ids) {
+                Some(request_id) => request_id,
+                None => break,
+            };
+
+            env.storage()
+                .instance()
+                .remove(&DataKey::Requests(request_id));
+            remaining -= 1;
+        }
+
+        let entries_drained = Self::drain_table_entries(&env, &table_id, &mut remaining);
+
+        if Self::list_len(&env, &members) == 0
+            && admins.is_empty()
+            && Self::list_len(&env, &request_ids) == 0
+            && entries_drained
+        {
+            env.storage()
+                .instance()
+                .remove(&DataKey::TableTagRules(table_id.clone()));
+            env.storage()
+                .instance()
+                .remove(&DataKey::TableAdminList(table_id.clone()));
+            env.storage()
+                .instance()
+                .remove(&DataKey::Tables(table_id.clone()));
+            summary.cleanup_complete = true;
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::TableSummaries(table_id), &summary);
+
+        summary.cleanup_complete
+    }
+
+    /// Removes up to `remaining` per-table bans, allowlist entries, invites,
+    /// blocklist entries and venue license indexes. Returns whether all of
+    /// them are gone.
+    fn drain_table_entries(env: &Env, table_id: &BytesN<32>, remaining: &mut u32) -> bool {
+        let address_lists = [
+            IndexedList::TableBans(table_id.clone()),
+            IndexedList::TableAllowlist(table_id.clone()),
+            IndexedList::TableBlockedArtists(table_id.clone()),
+        ];
+        for list in address_lists.iter() {
+            while *remaining > 0 {
+                let entry: Address = match Self::list_pop(env, list) {
+                    Some(entry) => entry,
+                    None => break,
+                };
+                let key = match list {
+                    IndexedList::TableBans(_) => DataKey::TableBans(table_id.clone(), entry),
+                    IndexedList::TableAllowlist(_) => {
+                        DataKey::TableAllowlist(table_id.clone(), entry)
+                    }
+                    _ => DataKey::TableBlockedArtists(table_id.clone(), entry),
+                };
+                env.storage().instance().remove(&key);
+                *remaining -= 1;
+            }
+        }
+
+        let id_lists = [
+            IndexedList::TableInvites(table_id.clone()),
+            IndexedList::TableBlockedTracks(table_id.clone()),
+            IndexedList::TableLicenses(table_id.clone()),
+        ];
+        for list in id_lists.iter() {
+            while *remaining > 0 {
+                let entry: BytesN<32> = match Self::list_pop(env, list) {
+                    Some(entry) => entry,
+                    None => break,
+                };
+                let key = match list {
+                    IndexedList::TableInvites(_) => DataKey::TableInvites(table_id.clone(), entry),
+                    IndexedList::TableBlockedTracks(_) => {
+                        DataKey::TableBlockedTracks(table_id.clone(), entry)
+                    }
+                    _ => DataKey::TableLicenses(table_id.clone(), entry),
+                };
+                env.storage().instance().remove(&key);
+                *remaining -= 1;
+            }
+        }
+
+        address_lists
+            .iter()
+            .chain(id_lists.iter())
+            .all(|list| Self::list_len(env, list) == 0)
+    }
+
+    pub fn get_table_summary(env: Env, table_id: BytesN<32>) -> Option<TableSummary> {
+        env.storage()
+            .instance()
+            .get(&DataKey::TableSummaries(table_id))
+    }
+
     pub fn has_voted_to_skip(env: Env, user: Address, table_id: BytesN<32>) -> bool {
         let table: JukeboxTable = env
             .storage()
@@ -812,7 +5759,7 @@ impl MetaJuke {
             }
         }
 
-        Self::advance_queue(&env, table_id)
+        Self::advance_queue(&env, table_id, false)
     }
 
     pub fn get_total_tracks(env: Env) -> u32 {
@@ -849,97 +5796,549 @@ impl MetaJuke {
 
         (total_tracks, total_tables, total_requests)
     }
-    pub fn get_artist_tracks(env: Env, artist: Address) -> Vec<BytesN<32>> {
-        let mut tracks = Vec::new(&env);
+
+    pub fn get_leaderboard(
+        env: Env,
+        kind: LeaderboardKind,
+        window: LeaderboardWindow,
+    ) -> Vec<LeaderboardEntry> {
+        let entries = Self::leaderboard_candidates(&env, kind, window);
+        if entries.len() > LEADERBOARD_SIZE {
+            entries.slice(0..LEADERBOARD_SIZE)
+        } else {
+            entries
+        }
+    }
+    pub fn get_artist_tracks(
+        env: Env,
+        artist: Address,
+        cursor: u32,
+        limit: u32,
+    ) -> (Vec<BytesN<32>>, Option<u32>) {
+        if !env
+            .storage()
+            .instance()
+            .has(&DataKey::Artists(artist.clone()))
+        {
+            panic!("Artist not found");
+        }
+
+        Self::list_page(&env, &IndexedList::ArtistTracks(artist), cursor, limit)
+    }
+
+    pub fn get_user_tables(
+        env: Env,
+        user: Address,
+        cursor: u32,
+        limit: u32,
+    ) -> (Vec<BytesN<32>>, Option<u32>) {
+        Self::list_page(&env, &IndexedList::UserTables(user), cursor, limit)
+    }
+
+    pub fn get_table_members(
+        env: Env,
+        table_id: BytesN<32>,
+        cursor: u32,
+        limit: u32,
+    ) -> (Vec<Address>, Option<u32>) {
+        if !env
+            .storage()
+            .instance()
+            .has(&DataKey::Tables(table_id.clone()))
+        {
+            panic!("Table not found");
+        }
+
+        Self::list_page(&env, &IndexedList::TableMembers(table_id), cursor, limit)
+    }
+
+    pub fn get_table_requests(
+        env: Env,
+        table_id: BytesN<32>,
+        cursor: u32,
+        limit: u32,
+    ) -> (Vec<TrackRequest>, Option<u32>) {
+        let (page, next_cursor): (Vec<BytesN<32>>, Option<u32>) =
+            Self::list_page(&env, &IndexedList::TableRequests(table_id), cursor, limit);
+
+        let mut requests = Vec::new(&env);
+        for request_id in page.iter() {
+            if let Some(request) = env.storage().instance().get(&DataKey::Requests(request_id)) {
+                requests.push_back(request);
+            }
+        }
+        (requests, next_cursor)
+    }
+
+    /// Pages through a list stored inline. Only for lists with a hard cap, such
+    /// as a table queue (`MAX_QUEUE_LEN`); anything that grows with users uses
+    /// an `IndexedList` and `list_page` instead.
+    fn paginate<T>(env: &Env, items: &Vec<T>, cursor: u32, limit: u32) -> (Vec<T>, Option<u32>)
+    where
+        T: IntoVal<Env, Val> + TryFromVal<Env, Val>,
+    {
+        if limit == 0 || limit > MAX_PAGE_SIZE {
+            panic!("Invalid page size");
+        }
+
+        if cursor >= items.len() {
+            return (Vec::new(env), None);
+        }
+
+        let end = items.len().min(cursor + limit);
+        let next_cursor = if end < items.len() { Some(end) } else { None };
+        (items.slice(cursor..end), next_cursor)
+    }
+
+    fn list_page<T>(env: &Env, list: &IndexedList, cursor: u32, limit: u32) -> (Vec<T>, Option<u32>)
+    where
+        T: IntoVal<Env, Val> + TryFromVal<Env, Val>,
+    {
+        if limit == 0 || limit > MAX_PAGE_SIZE {
+            panic!("Invalid page size");
+        }
+
+        let len = Self::list_len(env, list);
+        let mut page = Vec::new(env);
+        if cursor >= len {
+            return (page, None);
+        }
+
+        let end = len.min(cursor + limit);
+        for index in cursor..end {
+            page.push_back(Self::list_get(env, list, index));
+        }
+        let next_cursor = if end < len { Some(end) } else { None };
+        (page, next_cursor)
+    }
+
+    fn list_len(env: &Env, list: &IndexedList) -> u32 {
+        let key = DataKey::ListLength(list.clone());
+        let len = env.storage().persistent().get(&key).unwrap_or(0);
+        if len > 0 {
+            env.storage()
+                .persistent()
+                .extend_ttl(&key, LIST_TTL_THRESHOLD, LIST_TTL_EXTEND);
+        }
+        len
+    }
+
+    fn list_get<T>(env: &Env, list: &IndexedList, index: u32) -> T
+    where
+        T: TryFromVal<Env, Val>,
+    {
+        let key = DataKey::ListItems(list.clone(), index);
+        let item = env.storage().persistent().get(&key).unwrap();
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, LIST_TTL_THRESHOLD, LIST_TTL_EXTEND);
+        item
+    }
+
+    fn list_set<K, V>(env: &Env, key: &K, value: &V)
+    where
+        K: IntoVal<Env, Val>,
+        V: IntoVal<Env, Val>,
+    {
+        env.storage().persistent().set(key, value);
+        env.storage()
+            .persistent()
+            .extend_ttl(key, LIST_TTL_THRESHOLD, LIST_TTL_EXTEND);
+    }
+
+    fn list_contains<T>(env: &Env, list: &IndexedList, item: &T) -> bool
+    where
+        T: IntoVal<Env, Val> + Clone,
+    {
+        env.storage().persistent().has(&DataKey::ListPositions(
+            list.clone(),
+            item.clone().into_val(env),
+        ))
+    }
+
+    /// Appends `item` unless it is already listed. Returns whether it was added.
+    fn list_push<T>(env: &Env, list: &IndexedList, item: &T) -> bool
+    where
+        T: IntoVal<Env, Val> + Clone,
+    {
+        if Self::list_contains(env, list, item) {
+            return false;
+        }
+
+        let len = Self::list_len(env, list);
+        Self::list_set(env, &DataKey::ListItems(list.clone(), len), item);
+        Self::list_set(
+            env,
+            &DataKey::ListPositions(list.clone(), item.clone().into_val(env)),
+            &len,
+        );
+        Self::list_set(env, &DataKey::ListLength(list.clone()), &(len + 1));
+        true
+    }
+
+    /// Removes `item` by moving the last entry into its slot. Returns whether
+    /// it was listed.
+    fn list_remove<T>(env: &Env, list: &IndexedList, item: &T) -> bool
+    where
+        T: IntoVal<Env, Val> + TryFromVal<Env, Val> + Clone,
+    {
+        let position_key = DataKey::ListPositions(list.clone(), item.clone().into_val(env));
+        let position: u32 = match env.storage().persistent().get(&position_key) {
+            Some(position) => position,
+            None => return false,
+        };
+
+        let last = Self::list_len(env, list) - 1;
+        if position != last {
+            let moved: T = Self::list_get(env, list, last);
+            Self::list_set(
+                env,
+                &DataKey::ListPositions(list.clone(), moved.clone().into_val(env)),
+                &position,
+            );
+            Self::list_set(env, &DataKey::ListItems(list.clone(), position), &moved);
+        }
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::ListItems(list.clone(), last));
+        env.storage().persistent().remove(&position_key);
+        if last == 0 {
+            env.storage()
+                .persistent()
+                .remove(&DataKey::ListLength(list.clone()));
+        } else {
+            Self::list_set(env, &DataKey::ListLength(list.clone()), &last);
+        }
+        true
+    }
+
+    fn list_pop<T>(env: &Env, list: &IndexedList) -> Option<T>
+    where
+        T: IntoVal<Env, Val> + TryFromVal<Env, Val> + Clone,
+    {
+        let len = Self::list_len(env, list);
+        if len == 0 {
+            return None;
+        }
+
+        let item: T = Self::list_get(env, list, len - 1);
+        Self::list_remove(env, list, &item);
+        Some(item)
+    }
+
+    
+    pub fn update_artist_verification(env: Env, admin: Address, artist: Address, verified: bool) {
+        let stored_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic!("Admin not set"));
+
+        if admin != stored_admin {
+            panic!("Not authorized");
+        }
+        admin.require_auth();
+
+        let status = if verified {
+            VerificationStatus::Approved
+        } else {
+            VerificationStatus::Revoked
+        };
+        Self::record_verification(&env, &artist, None, status, &admin, None);
+    }
+
+    pub fn add_verifier(env: Env, verifier: Address) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        env.storage()
+            .instance()
+            .set(&DataKey::Verifiers(verifier), &true);
+    }
+
+    pub fn remove_verifier(env: Env, verifier: Address) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        env.storage()
+            .instance()
+            .remove(&DataKey::Verifiers(verifier));
+    }
+
+    pub fn update_verification_privileges(
+        env: Env,
+        unverified_price_cap: i128,
+        verified_price_cap: i128,
+        max_featured_per_artist: u32,
+    ) {
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        if unverified_price_cap < 0 || verified_price_cap < 0 {
+            panic!("Invalid price cap");
+        }
+
+        env.storage().instance().set(
+            &DataKey::VerificationPrivileges,
+            &VerificationPrivileges {
+                unverified_price_cap,
+                verified_price_cap,
+                max_featured_per_artist,
+            },
+        );
+    }
+
+    pub fn submit_verification(env: Env, artist: Address, evidence_uri: String) -> u32 {
+        artist.require_auth();
+        Self::require_active_user(&env, &artist);
+
         let artist_data: Artist = env
             .storage()
             .instance()
             .get(&DataKey::Artists(artist.clone()))
             .unwrap_or_else(|| panic!("Artist not found"));
 
-        
-        let track_counter: u32 = env
+        if artist_data.verified {
+            panic!("Artist already verified");
+        }
+
+        let record_count: u32 = env
             .storage()
             .instance()
-            .get(&DataKey::TrackIdCounter)
+            .get(&DataKey::VerificationCount(artist.clone()))
             .unwrap_or(0);
-
-        for i in 1..=track_counter {
-            if env.storage().instance().has(&DataKey::ArtistTracks(
-                artist.clone(),
-                BytesN::from_array(&env, &[i as u8; 32]),
-            )) {
-                tracks.push_back(BytesN::from_array(&env, &[i as u8; 32]));
+        if let Some(latest) =
+            env.storage()
+                .instance()
+                .get::<_, VerificationRecord>(&DataKey::VerificationRecords(
+                    artist.clone(),
+                    record_count,
+                ))
+        {
+            if latest.status == VerificationStatus::Pending {
+                panic!("Application already pending");
             }
         }
-        tracks
+
+        let record_id = record_count + 1;
+        let record = VerificationRecord {
+            record_id,
+            artist: artist.clone(),
+            evidence_uri: Some(evidence_uri),
+            submitted_at: env.ledger().timestamp(),
+            status: VerificationStatus::Pending,
+            reviewer: None,
+            reason: None,
+            reviewed_at: None,
+        };
+
+        env.storage().instance().set(
+            &DataKey::VerificationRecords(artist.clone(), record_id),
+            &record,
+        );
+        env.storage()
+            .instance()
+            .set(&DataKey::VerificationCount(artist.clone()), &record_id);
+
+        env.events().publish(
+            (Symbol::new(&env, "verification_submitted"), artist),
+            record_id,
+        );
+
+        record_id
     }
-    pub fn get_user_tables(env: Env, user: Address) -> Vec<BytesN<32>> {
-        let mut tables = Vec::new(&env);
-        let table_counter: u32 = env
+
+    pub fn review_verification(
+        env: Env,
+        verifier: Address,
+        artist: Address,
+        approve: bool,
+        reason: String,
+    ) {
+        verifier.require_auth();
+
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        if verifier != admin
+            && !env
+                .storage()
+                .instance()
+                .has(&DataKey::Verifiers(verifier.clone()))
+        {
+            panic!("Not a verifier");
+        }
+
+        let record_id: u32 = env
             .storage()
             .instance()
-            .get(&DataKey::TableIdCounter)
+            .get(&DataKey::VerificationCount(artist.clone()))
             .unwrap_or(0);
+        let mut record: VerificationRecord = env
+            .storage()
+            .instance()
+            .get(&DataKey::VerificationRecords(artist.clone(), record_id))
+            .unwrap_or_else(|| panic!("No pending application"));
 
-        for i in 1..=table_counter {
-            let table_id = BytesN::from_array(&env, &[i as u8; 32]);
-            if env
+        if record.status != VerificationStatus::Pending {
+            panic!("No pending application");
+        }
+
+        if record.artist == verifier {
+            panic!("Cannot review own application");
+        }
+
+        record.status = if approve {
+            VerificationStatus::Approved
+        } else {
+            VerificationStatus::Rejected
+        };
+        record.reviewer = Some(verifier);
+        record.reason = Some(reason);
+        record.reviewed_at = Some(env.ledger().timestamp());
+
+        env.storage().instance().set(
+            &DataKey::VerificationRecords(artist.clone(), record_id),
+            &record,
+        );
+        Self::set_artist_verified(&env, &artist, approve);
+
+        env.events().publish(
+            (Symbol::new(&env, "verification_reviewed"), artist),
+            (record_id, approve),
+        );
+    }
+
+    pub fn feature_track(env: Env, artist: Address, track_id: BytesN<32>, featured: bool) {
+        artist.require_auth();
+
+        let track: Track = env
+            .storage()
+            .instance()
+            .get(&DataKey::Tracks(track_id.clone()))
+            .unwrap_or_else(|| panic!("Track not found"));
+
+        if track.artist_id != artist {
+            panic!("Not track owner");
+        }
+
+        let artist_featured = IndexedList::ArtistFeaturedTracks(artist.clone());
+        if featured {
+            let artist_data: Artist = env
                 .storage()
                 .instance()
-                .has(&DataKey::TableMembers(table_id.clone(), user.clone()))
-            {
-                tables.push_back(table_id);
+                .get(&DataKey::Artists(artist.clone()))
+                .unwrap();
+            if !artist_data.verified {
+                panic!("Featured placement requires verification");
+            }
+
+            if Self::list_contains(&env, &IndexedList::FeaturedTracks, &track_id) {
+                panic!("Track already featured");
+            }
+
+            let privileges = Self::verification_privileges(&env);
+            if Self::list_len(&env, &artist_featured) >= privileges.max_featured_per_artist {
+                panic!("Featured track limit reached");
             }
+
+            Self::list_push(&env, &IndexedList::FeaturedTracks, &track_id);
+            Self::list_push(&env, &artist_featured, &track_id);
+        } else {
+            Self::list_remove(&env, &IndexedList::FeaturedTracks, &track_id);
+            Self::list_remove(&env, &artist_featured, &track_id);
         }
-        tables
     }
-  
-    
-    
-    pub fn get_table_members(env: Env, table_id: BytesN<32>) -> Vec<Address> {
-        let mut members = Vec::new(&env);
 
-        
-        if !env
-            .storage()
-            .instance()
-            .has(&DataKey::Tables(table_id.clone()))
-        {
-            panic!("Table not found");
-        }  
-        
-        
+    pub fn get_featured_tracks(
+        env: Env,
+        cursor: u32,
+        limit: u32,
+    ) -> (Vec<BytesN<32>>, Option<u32>) {
+        Self::list_page(&env, &IndexedList::FeaturedTracks, cursor, limit)
+    }
+
+    pub fn get_verification_history(
+        env: Env,
+        artist: Address,
+        cursor: u32,
+        limit: u32,
+    ) -> (Vec<VerificationRecord>, Option<u32>) {
+        if limit == 0 || limit > MAX_PAGE_SIZE {
+            panic!("Invalid page size");
+        }
 
-        
-        
-        if let Some(members_list) = env
+        let record_count: u32 = env
             .storage()
             .instance()
-            .get(&DataKey::TableMemberList(table_id.clone()))
-        {
-            return members_list;
+            .get(&DataKey::VerificationCount(artist.clone()))
+            .unwrap_or(0);
+
+        let mut history = Vec::new(&env);
+        if cursor >= record_count {
+            return (history, None);
+        }
+
+        let end = record_count.min(cursor + limit);
+        for record_id in (cursor + 1)..=end {
+            if let Some(record) = env
+                .storage()
+                .instance()
+                .get(&DataKey::VerificationRecords(artist.clone(), record_id))
+            {
+                history.push_back(record);
+            }
         }
 
-        members
+        let next_cursor = if end < record_count { Some(end) } else { None };
+        (history, next_cursor)
     }
 
-    pub fn get_table_requests(env: Env, table_id: BytesN<32>) -> Vec<TrackRequest> {
-      
+    pub fn is_verifier(env: Env, user: Address) -> bool {
+        env.storage().instance().has(&DataKey::Verifiers(user))
     }
 
-    
-    pub fn update_artist_verification(env: Env, admin: Address, artist: Address, verified: bool) {
-        let stored_admin: Address = env
+    pub fn get_verification_privileges(env: Env) -> VerificationPrivileges {
+        Self::verification_privileges(&env)
+    }
+
+    fn record_verification(
+        env: &Env,
+        artist: &Address,
+        evidence_uri: Option<String>,
+        status: VerificationStatus,
+        reviewer: &Address,
+        reason: Option<String>,
+    ) {
+        let record_count: u32 = env
             .storage()
             .instance()
-            .get(&DataKey::Admin)
-            .unwrap_or_else(|| panic!("Admin not set"));
+            .get(&DataKey::VerificationCount(artist.clone()))
+            .unwrap_or(0);
+        let record_id = record_count + 1;
+
+        let record = VerificationRecord {
+            record_id,
+            artist: artist.clone(),
+            evidence_uri,
+            submitted_at: env.ledger().timestamp(),
+            status,
+            reviewer: Some(reviewer.clone()),
+            reason,
+            reviewed_at: Some(env.ledger().timestamp()),
+        };
 
-        if admin != stored_admin {
-            panic!("Not authorized");
-        }
+        env.storage().instance().set(
+            &DataKey::VerificationRecords(artist.clone(), record_id),
+            &record,
+        );
+        env.storage()
+            .instance()
+            .set(&DataKey::VerificationCount(artist.clone()), &record_id);
+
+        Self::set_artist_verified(env, artist, status == VerificationStatus::Approved);
+    }
 
+    fn set_artist_verified(env: &Env, artist: &Address, verified: bool) {
         let mut artist_data: Artist = env
             .storage()
             .instance()
@@ -949,6 +6348,44 @@ impl MetaJuke {
         artist_data.verified = verified;
         env.storage()
             .instance()
-            .set(&DataKey::Artists(artist), &artist_data);
+            .set(&DataKey::Artists(artist.clone()), &artist_data);
+
+        if !verified {
+            // Bounded by `max_featured_per_artist`, so this never walks the
+            // global featured list.
+            let artist_featured = IndexedList::ArtistFeaturedTracks(artist.clone());
+            while let Some(track_id) = Self::list_pop::<BytesN<32>>(env, &artist_featured) {
+                Self::list_remove(env, &IndexedList::FeaturedTracks, &track_id);
+            }
+        }
+    }
+
+    fn verification_privileges(env: &Env) -> VerificationPrivileges {
+        env.storage()
+            .instance()
+            .get(&DataKey::VerificationPrivileges)
+            .unwrap_or(VerificationPrivileges {
+                unverified_price_cap: 0,
+                verified_price_cap: 0,
+                max_featured_per_artist: 1,
+            })
+    }
+
+    fn check_price_cap(env: &Env, artist: &Address, base_price: i128) {
+        let artist_data: Artist = env
+            .storage()
+            .instance()
+            .get(&DataKey::Artists(artist.clone()))
+            .unwrap_or_else(|| panic!("Not registered as artist"));
+        let privileges = Self::verification_privileges(env);
+
+        let price_cap = if artist_data.verified {
+            privileges.verified_price_cap
+        } else {
+            privileges.unverified_price_cap
+        };
+        if price_cap > 0 && base_price > price_cap {
+            panic!("Base price above cap");
+        }
     }
 }